@@ -0,0 +1,29 @@
+use avatar_graph::Graph;
+use std::time::Instant;
+
+fn main() {
+    let g = Graph::hypercube(4);
+    let n = g.nodes.len();
+    let runs = 20;
+
+    let start = Instant::now();
+    for _ in 0..runs {
+        assert!((0..n).all(|i| g.is_avatar_graph(i)));
+    }
+    let uncached = start.elapsed() / runs;
+
+    let start = Instant::now();
+    let cache = g.build_cache();
+    let build = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..runs {
+        assert!((0..n).all(|i| g.is_avatar_graph_cached(i, &cache)));
+    }
+    let cached = start.elapsed() / runs;
+
+    println!("hypercube(4) build_cache:             {:?}", build);
+    println!("hypercube(4) is_avatar_graph:         {:?}", uncached);
+    println!("hypercube(4) is_avatar_graph_cached:  {:?}", cached);
+    println!("speedup: {:.2}x", uncached.as_secs_f64() / cached.as_secs_f64());
+}
@@ -0,0 +1,25 @@
+use avatar_graph::Graph;
+use std::time::Instant;
+
+fn main() {
+    let g = Graph::hypercube(5);
+    let runs = 5;
+
+    let start = Instant::now();
+    for _ in 0..runs {
+        let mut g = g.clone();
+        g.corify();
+    }
+    let sequential = start.elapsed() / runs;
+
+    let start = Instant::now();
+    for _ in 0..runs {
+        let mut g = g.clone();
+        g.corify_parallel();
+    }
+    let parallel = start.elapsed() / runs;
+
+    println!("hypercube(5) corify:          {:?}", sequential);
+    println!("hypercube(5) corify_parallel: {:?}", parallel);
+    println!("speedup: {:.2}x", sequential.as_secs_f64() / parallel.as_secs_f64());
+}
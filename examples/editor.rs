@@ -9,6 +9,8 @@ fn main() {
     println!("Space - Add new node");
     println!("S - Select start node");
     println!("C - Connect to closest node");
+    println!("D - Disconnect from closest node");
+    println!("Delete - Remove closest node");
     println!("H - Hide selected start node");
     println!("P - Proof mode (shows why node is not a core)");
     println!("A - Show avatar distance");
@@ -48,7 +50,7 @@ fn main() {
                 let border = ellipse::Ellipse::new_border([0.0, 0.0, 0.0, 1.0], 1.0);
                 let radius = 10.0;
 
-                for i in 0..graph.nodes.len() {
+                for i in 0..graph.node_count() {
                     if let Some(j) = graph.nodes[i].uniq {
                         let a = node_pos[i];
                         let b = node_pos[j];
@@ -82,61 +84,30 @@ fn main() {
 
                 if proof_mode && node_pos.len() > 0 {
                     if !graph.nodes[selected].core {
-                        let mut nodes = vec![];
-                        let mut color = [1.0; 4];
-                        let contractibles = graph.contractibles_of(selected);
                         let mut max_avatar: Option<usize> = None;
-                        if contractibles.len() > 0 {
+                        let (nodes, color) = match graph.validate(selected) {
                             // Show nodes that are contractible.
-                            nodes = contractibles;
-                            color = [1.0, 0.0, 0.0, 1.0];
-                        }
-                        if nodes.len() == 0 {
-                            let dist = graph.distance(selected);
-                            match dist {
-                                Err(dist) => {
-                                    // Show nodes that are unreachable from selected node.
-                                    let mut res = vec![];
-                                    for i in 0..graph.nodes.len() {
-                                        if dist.binary_search_by(|n| n.0.cmp(&i)).is_err() {
-                                            res.push(i);
-                                        }
-                                    }
-                                    nodes = res;
-                                    color = [0.0, 0.0, 1.0, 1.0];
-                                }
-                                Ok(_) => {}
-                            }
-                        }
-                        if nodes.len() == 0 {
-                            let max_avatars = graph.max_avatars(selected);
-                            if max_avatars.1.len() > 1 {
-                                // Show max avatars.
-                                nodes = max_avatars.1;
-                                color = [0.0, 1.0, 0.0, 1.0];
-                            }
-                        }
-                        if nodes.len() == 0 {
-                            let max_avatars = graph.max_avatars(selected);
-                            max_avatar = Some(max_avatars.1[0]);
-                            if let Ok(along) = graph.along(max_avatars.1[0], selected) {
-                                // Show nodes that are not reachable along the path.
-                                let mut res = vec![];
-                                for i in 0..graph.nodes.len() {
-                                    if along.binary_search_by(|n| n.cmp(&i)).is_err() {
-                                        res.push(i);
-                                    }
+                            Err(AvatarGraphError::Contractible(nodes)) =>
+                                (nodes, [1.0, 0.0, 0.0, 1.0]),
+                            // Show nodes that are unreachable from selected node.
+                            Err(AvatarGraphError::Disconnected(nodes)) =>
+                                (nodes, [0.0, 0.0, 1.0, 1.0]),
+                            // Show max avatars.
+                            Err(AvatarGraphError::NonUniqueMaxAvatar(nodes)) =>
+                                (nodes, [0.0, 1.0, 0.0, 1.0]),
+                            err => {
+                                max_avatar = Some(graph.max_avatars(selected).1[0]);
+                                match err {
+                                    // Show nodes that are not reachable along the path.
+                                    Err(AvatarGraphError::NotUniversallyReachable(nodes)) =>
+                                        (nodes, [0.0, 0.7, 1.0, 1.0]),
+                                    // Show avatar connectivity failures.
+                                    Err(AvatarGraphError::AvatarConnectivityViolation(nodes)) =>
+                                        (nodes, [1.0, 0.7, 0.0, 1.0]),
+                                    _ => (vec![], [1.0; 4]),
                                 }
-                                nodes = res;
-                                color = [0.0, 0.7, 1.0, 1.0];
                             }
-                        }
-                        if nodes.len() == 0 {
-                            // Show avatar connectivity failures.
-                            nodes = graph.avatar_connectivity_failures_of(selected);
-                            color = [1.0, 0.7, 0.0, 1.0];
-                        }
-
+                        };
                         for &i in &nodes {
                             ellipse::Ellipse::new_border(color, 2.0).draw([
                                     node_pos[i][0] - radius,
@@ -208,6 +179,23 @@ fn main() {
                     }
                 }
             }
+            if let Button::Keyboard(Key::D) = button {
+                if let Some((i, _)) = min_pos(&node_pos, cursor) {
+                    if graph.remove_edge(selected, i) {
+                        graph.corify();
+                    }
+                }
+            }
+            if let Button::Keyboard(Key::Delete) = button {
+                if let Some((i, _)) = min_pos(&node_pos, cursor) {
+                    node_pos.remove(i);
+                    graph.remove_node(i);
+                    if selected > i || selected >= node_pos.len() {
+                        selected = selected.saturating_sub(1);
+                    }
+                    graph.corify();
+                }
+            }
             if let Button::Keyboard(Key::H) = button {
                 hide = !hide;
             }
@@ -164,44 +164,248 @@
 //! This property is beneficial in systems where you want to have choices,
 //! but you also want to avoid regression.
 
+use std::hash::{Hash, Hasher};
+
+pub mod named_graphs;
+
 /// Represents a node in the graph.
-#[derive(Debug, Clone)]
-pub struct Node {
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Node<L = ()> {
     /// Whether the node is a core.
     pub core: bool,
     /// A unique edge to the highest avatar.
     pub uniq: Option<usize>,
+    /// A user defined label.
+    pub label: L,
 }
 
+/// A node without a label.
+pub type UnitNode = Node<()>;
+
 impl Node {
     /// Creates a new node with no unique connection.
     pub fn new(core: bool) -> Node {
         Node {
-            core, uniq: None
+            core, uniq: None, label: ()
+        }
+    }
+}
+
+impl<L> Node<L> {
+    /// Creates a new labeled node with no unique connection.
+    pub fn with_label(core: bool, label: L) -> Node<L> {
+        Node {
+            core, uniq: None, label
         }
     }
 }
 
+/// Describes why a node is not a core of an Avatar Graph.
+///
+/// Each variant carries the nodes that caused the violation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AvatarGraphError {
+    /// Some nodes are contractible.
+    Contractible(Vec<usize>),
+    /// Some nodes can not be reached from the core.
+    Disconnected(Vec<usize>),
+    /// There is more than one max avatar.
+    NonUniqueMaxAvatar(Vec<usize>),
+    /// Some nodes are not reachable when walking from the max avatar to the core.
+    NotUniversallyReachable(Vec<usize>),
+    /// Some nodes have wrong avatar connectivity.
+    AvatarConnectivityViolation(Vec<usize>),
+}
+
 /// Represents an Avatar Graph.
-#[derive(Debug, Clone)]
-pub struct Graph {
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Graph<L = ()> {
     /// Stores nodes.
-    pub nodes: Vec<Node>,
+    pub nodes: Vec<Node<L>>,
     /// Stores edges between nodes.
     pub edges: Vec<(usize, usize)>,
 }
 
-impl Graph {
-    /// Creates a new empty graph.
-    pub fn new() -> Graph {
+impl<L> Default for Graph<L> {
+    fn default() -> Graph<L> {
         Graph {
             nodes: vec![],
             edges: vec![],
         }
     }
+}
+
+impl Graph {
+    /// Creates a new empty graph.
+    pub fn new() -> Graph {
+        Graph::default()
+    }
+
+    /// Creates an n-dimensional hypercube.
+    ///
+    /// Nodes are indexed by their binary coordinates,
+    /// with edges between nodes that differ in exactly one bit.
+    pub fn hypercube(n: usize) -> Graph {
+        let mut g = Graph {
+            nodes: vec![Node::new(false); 1 << n],
+            edges: Vec::with_capacity(n * (1 << n) / 2),
+        };
+        for i in 0..1 << n {
+            for bit in 0..n {
+                let j = i ^ (1 << bit);
+                if i < j {g.edges.push((i, j))}
+            }
+        }
+        g
+    }
+
+    /// Creates a path with `n` nodes.
+    ///
+    /// Node `i` is connected to node `i + 1`.
+    pub fn path(n: usize) -> Graph {
+        Graph::from_edges(n, (1..n).map(|i| (i - 1, i)), vec![])
+    }
+
+    /// Creates a cycle with `n` nodes.
+    ///
+    /// Same as a path, but the last node is connected to the first.
+    /// For `n < 3` this is a path.
+    pub fn cycle(n: usize) -> Graph {
+        let mut g = Graph::path(n);
+        if n >= 3 {g.add_edge(0, n - 1);}
+        g
+    }
+
+    /// Creates a complete graph with `n` nodes.
+    pub fn complete(n: usize) -> Graph {
+        Graph::from_edges(n, (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j))), vec![])
+    }
+
+    /// Creates a graph with `n` nodes from a list of edges.
+    ///
+    /// The core flag of each node is taken from `cores`.
+    /// Nodes without a core flag are not cores.
+    pub fn from_edges(
+        n: usize,
+        edges: impl IntoIterator<Item = (usize, usize)>,
+        cores: impl IntoIterator<Item = bool>
+    ) -> Graph {
+        let mut g = Graph::new();
+        g.reserve_nodes(n);
+        for core in cores.into_iter().chain(std::iter::repeat(false)).take(n) {
+            g.add_node(Node::new(core));
+        }
+        let edges = edges.into_iter();
+        g.reserve_edges(edges.size_hint().0);
+        for (a, b) in edges {
+            g.add_edge(a, b);
+        }
+        g
+    }
+
+    /// Creates a graph from an adjacency list.
+    ///
+    /// Entry `i` lists the neighbors of node `i`, and `cores` the core flag of each node.
+    /// Returns `Err` if the lists are not symmetric or refer to missing nodes.
+    pub fn from_adjacency_list(adj: &[Vec<usize>], cores: &[bool]) -> Result<Graph, String> {
+        let n = adj.len();
+        if cores.len() != n {
+            return Err(format!("Expected {} core flags, found {}", n, cores.len()));
+        }
+        let mut g = Graph::new();
+        g.reserve_nodes(n);
+        for &core in cores {
+            g.add_node(Node::new(core));
+        }
+        for (i, neighbors) in adj.iter().enumerate() {
+            for &j in neighbors {
+                if j >= n {
+                    return Err(format!("Node {} is connected to missing node {}", i, j));
+                }
+                if !adj[j].contains(&i) {
+                    return Err(format!("Node {} is connected to {}, but not the other way", i, j));
+                }
+                if i <= j {g.add_edge(i, j);}
+            }
+        }
+        Ok(g)
+    }
+
+    /// Creates the Cartesian product of two graphs.
+    ///
+    /// Node `(u, v)` has index `u * h.nodes.len() + v`.
+    /// Nodes `(u, v)` and `(u', v')` are connected when either `u == u'`
+    /// and `v` is connected to `v'`, or `v == v'` and `u` is connected to `u'`.
+    /// A node is a core if both its factors are cores.
+    pub fn cartesian_product<L, M>(g: &Graph<L>, h: &Graph<M>) -> Graph {
+        let m = h.nodes.len();
+        let mut res = Graph::new();
+        for a in &g.nodes {
+            for b in &h.nodes {
+                res.add_node(Node::new(a.core && b.core));
+            }
+        }
+        for &(a, b) in &g.edges {
+            for v in 0..m {
+                res.edges.push((a * m + v, b * m + v));
+            }
+        }
+        for u in 0..g.nodes.len() {
+            for &(a, b) in &h.edges {
+                res.edges.push((u * m + a, u * m + b));
+            }
+        }
+        res
+    }
+}
+
+impl<L> Graph<L> {
+    /// Creates the disjoint union of two graphs.
+    ///
+    /// Nodes of `h` are placed after nodes of `g`,
+    /// with indices offset by `g.nodes.len()`.
+    pub fn merge(g: &Graph<L>, h: &Graph<L>) -> Graph<L>
+        where L: Clone
+    {
+        let mut res = g.clone();
+        res.append(h);
+        res
+    }
+
+    /// Merges another graph into this one and connects them with an edge.
+    ///
+    /// The edge connects `a` in this graph with `b` in the other graph.
+    /// Returns the offset of the other graph's nodes.
+    pub fn merge_with_bridge(&mut self, other: &Graph<L>, a: usize, b: usize) -> usize
+        where L: Clone
+    {
+        let offset = self.append(other);
+        self.add_edge(a, b + offset);
+        offset
+    }
+
+    /// Appends nodes and edges of another graph, returning the offset.
+    fn append(&mut self, other: &Graph<L>) -> usize
+        where L: Clone
+    {
+        let offset = self.nodes.len();
+        for node in &other.nodes {
+            self.nodes.push(Node {
+                core: node.core,
+                uniq: node.uniq.map(|j| j + offset),
+                label: node.label.clone(),
+            });
+        }
+        for &(a, b) in &other.edges {
+            self.edges.push((a + offset, b + offset));
+        }
+        offset
+    }
 
     /// Adds a new node.
-    pub fn add_node(&mut self, node: Node) -> usize {
+    pub fn add_node(&mut self, node: Node<L>) -> usize {
         let id = self.nodes.len();
         self.nodes.push(node);
         id
@@ -219,6 +423,107 @@ impl Graph {
         id
     }
 
+    /// Removes an edge.
+    ///
+    /// Unique edges are not affected.
+    /// Returns `true` if the edge was present.
+    pub fn remove_edge(&mut self, a: usize, b: usize) -> bool {
+        let min = a.min(b);
+        let max = a.max(b);
+        let len = self.edges.len();
+        self.edges.retain(|&e| e != (min, max));
+        self.edges.len() < len
+    }
+
+    /// Removes a node and all edges connected to it.
+    ///
+    /// Nodes with higher index are shifted down by one,
+    /// both in edges and in unique edges.
+    /// Unique edges to the removed node are removed.
+    ///
+    /// Returns `None` if there is no node with the index.
+    pub fn remove_node(&mut self, id: usize) -> Option<Node<L>> {
+        if id >= self.nodes.len() {return None};
+        self.edges.retain(|&(a, b)| a != id && b != id);
+        for e in &mut self.edges {
+            if e.0 > id {e.0 -= 1}
+            if e.1 > id {e.1 -= 1}
+        }
+        let node = self.nodes.remove(id);
+        for node in &mut self.nodes {
+            node.uniq = match node.uniq {
+                Some(j) if j == id => None,
+                Some(j) if j > id => Some(j - 1),
+                x => x,
+            };
+        }
+        Some(node)
+    }
+
+    /// Returns the complement graph.
+    ///
+    /// Two different nodes are connected in the complement
+    /// if and only if they are not connected in the original graph.
+    /// Cores are preserved, but unique edges are removed.
+    pub fn complement(&self) -> Graph<L>
+        where L: Clone
+    {
+        let n = self.nodes.len();
+        let mut adj = vec![vec![false; n]; n];
+        for &(a, b) in &self.edges {
+            adj[a][b] = true;
+            adj[b][a] = true;
+        }
+        let mut g = Graph::default();
+        for node in &self.nodes {
+            g.nodes.push(Node::with_label(node.core, node.label.clone()));
+        }
+        for (i, row) in adj.iter().enumerate() {
+            for (j, &connected) in row.iter().enumerate().skip(i + 1) {
+                if !connected {g.edges.push((i, j))}
+            }
+        }
+        g
+    }
+
+    /// Returns a graph with the same structure, but with new labels.
+    pub fn map_labels<M>(&self, f: impl Fn(&L) -> M) -> Graph<M> {
+        Graph {
+            nodes: self.nodes.iter().map(|node| Node {
+                core: node.core,
+                uniq: node.uniq,
+                label: f(&node.label),
+            }).collect(),
+            edges: self.edges.clone(),
+        }
+    }
+
+    /// Returns the number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Reserves capacity for at least `additional` more nodes.
+    pub fn reserve_nodes(&mut self, additional: usize) {
+        self.nodes.reserve(additional);
+    }
+
+    /// Reserves capacity for at least `additional` more edges.
+    pub fn reserve_edges(&mut self, additional: usize) {
+        self.edges.reserve(additional);
+    }
+
+    /// Shrinks the capacity of nodes and edges as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.nodes.shrink_to_fit();
+        self.edges.shrink_to_fit();
+    }
+
     /// Counts the number of cores.
     pub fn cores(&self) -> usize {
         let mut sum = 0;
@@ -233,6 +538,105 @@ impl Graph {
         self.nodes.len() - self.cores()
     }
 
+    /// Returns the number of edges connected to a node.
+    ///
+    /// A self edge is counted twice.
+    pub fn degree(&self, node: usize) -> usize {
+        self.edges.iter()
+            .map(|&(a, b)| (a == node) as usize + (b == node) as usize)
+            .sum()
+    }
+
+    /// Returns the degrees of all nodes, sorted in descending order.
+    pub fn degree_sequence(&self) -> Vec<usize> {
+        let mut res = self.degrees();
+        res.sort_by(|a, b| b.cmp(a));
+        res
+    }
+
+    /// Returns the maximum number of edges connected to a node.
+    pub fn max_degree(&self) -> usize {
+        self.degrees().into_iter().max().unwrap_or(0)
+    }
+
+    /// Returns the minimum number of edges connected to a node.
+    pub fn min_degree(&self) -> usize {
+        self.degrees().into_iter().min().unwrap_or(0)
+    }
+
+    /// Returns the average number of edges connected to a node.
+    pub fn avg_degree(&self) -> f64 {
+        if self.nodes.is_empty() {return 0.0};
+        2.0 * self.edges.len() as f64 / self.nodes.len() as f64
+    }
+
+    /// Returns the ratio of edges to the maximum number of edges.
+    ///
+    /// Assumes there are no self edges or duplicate edges.
+    /// Returns `0.0` for graphs with less than two nodes.
+    pub fn density(&self) -> f64 {
+        let n = self.nodes.len() as f64;
+        if n < 2.0 {return 0.0};
+        2.0 * self.edges.len() as f64 / (n * (n - 1.0))
+    }
+
+    /// Returns `Some(k)` if every node has `k` edges.
+    ///
+    /// Returns `None` if the graph is empty or nodes have different degrees.
+    pub fn is_regular(&self) -> Option<usize> {
+        let degrees = self.degrees();
+        let k = *degrees.first()?;
+        if degrees.iter().all(|&d| d == k) {Some(k)} else {None}
+    }
+
+    /// Returns nodes that have no edges.
+    ///
+    /// An isolated node can not be reached from any other node.
+    pub fn isolated_nodes(&self) -> Vec<usize> {
+        let degrees = self.degrees();
+        (0..self.nodes.len()).filter(|&i| degrees[i] == 0).collect()
+    }
+
+    /// Returns nodes that have exactly one edge.
+    ///
+    /// A leaf node is contractible relative to any core except itself and its neighbor.
+    pub fn leaf_nodes(&self) -> Vec<usize> {
+        let degrees = self.degrees();
+        (0..self.nodes.len()).filter(|&i| degrees[i] == 1).collect()
+    }
+
+    /// Returns the number of edges connected to each node.
+    fn degrees(&self) -> Vec<usize> {
+        let mut res = vec![0; self.nodes.len()];
+        for &(a, b) in &self.edges {
+            res[a] += 1;
+            res[b] += 1;
+        }
+        res
+    }
+
+    /// Returns the ratio of cores to nodes.
+    ///
+    /// A filled Avatar Graph, where every node is a core, has ratio `1.0`.
+    pub fn fill_ratio(&self) -> f64 {
+        if self.nodes.is_empty() {return 0.0};
+        self.cores() as f64 / self.nodes.len() as f64
+    }
+
+    /// Returns the sorted list of neighbors for every node.
+    pub fn to_adjacency_list(&self) -> Vec<Vec<usize>> {
+        let mut res = vec![vec![]; self.nodes.len()];
+        for &(a, b) in &self.edges {
+            res[a].push(b);
+            if a != b {res[b].push(a)}
+        }
+        for neighbors in &mut res {
+            neighbors.sort();
+            neighbors.dedup();
+        }
+        res
+    }
+
     /// Returns a list of nodes connected by edges of a node.
     pub fn edges_of(&self, node: usize) -> Vec<usize> {
         let mut res = vec![];
@@ -311,6 +715,58 @@ impl Graph {
         mat
     }
 
+    /// Returns the `k`-th power of the adjacency matrix.
+    ///
+    /// Entry `[i][j]` counts the number of walks of length `k` from `i` to `j`.
+    /// Counts that do not fit in `u64` saturate at `u64::MAX`.
+    /// Unique edges are not included.
+    pub fn matrix_power(&self, k: usize) -> Vec<Vec<u64>> {
+        let n = self.nodes.len();
+        let mut adj = vec![vec![0; n]; n];
+        for &(a, b) in &self.edges {
+            adj[a][b] = 1;
+            adj[b][a] = 1;
+        }
+        let mut res: Vec<Vec<u64>> = (0..n).map(|i| (0..n).map(|j| (i == j) as u64).collect()).collect();
+        for _ in 0..k {
+            let mut next = vec![vec![0; n]; n];
+            for i in 0..n {
+                for j in 0..n {
+                    next[i][j] = (0..n).fold(0, |sum: u64, m| sum.saturating_add(res[i][m] * adj[m][j]));
+                }
+            }
+            res = next;
+        }
+        res
+    }
+
+    /// Returns a Graphviz DOT representation of the graph.
+    ///
+    /// - Cores are black nodes
+    /// - Avatars are white nodes
+    /// - Edges are solid lines
+    /// - Unique edges are dashed lines
+    pub fn to_dot(&self, name: &str) -> String {
+        let mut res = format!("graph \"{}\" {{\n", name.replace('"', "\\\""));
+        for (i, node) in self.nodes.iter().enumerate() {
+            if node.core {
+                res.push_str(&format!("    {} [style=filled fillcolor=black fontcolor=white];\n", i));
+            } else {
+                res.push_str(&format!("    {} [style=filled fillcolor=white];\n", i));
+            }
+        }
+        for &(a, b) in &self.edges {
+            res.push_str(&format!("    {} -- {};\n", a, b));
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            if let Some(j) = node.uniq {
+                res.push_str(&format!("    {} -- {} [style=dashed];\n", i, j));
+            }
+        }
+        res.push_str("}\n");
+        res
+    }
+
     /// Assigns each node a distance number from a particular node.
     ///
     /// Returns `Ok` if the entire graph is connected.
@@ -363,6 +819,263 @@ impl Graph {
         Ok(dist)
     }
 
+    /// Returns shortest distances between all pairs of nodes.
+    ///
+    /// Entry `[i][j]` is `None` if `j` can not be reached from `i`.
+    /// Runs a breadth first search from every node.
+    pub fn all_pairs_distances(&self) -> Vec<Vec<Option<u64>>> {
+        let n = self.nodes.len();
+        let edges: Vec<Vec<usize>> = (0..n).map(|i| self.edges_of(i)).collect();
+        let mut res = vec![vec![None; n]; n];
+        for (i, dist) in res.iter_mut().enumerate() {
+            dist[i] = Some(0);
+            let mut queue = std::collections::VecDeque::new();
+            queue.push_back(i);
+            while let Some(j) = queue.pop_front() {
+                let d = dist[j].unwrap() + 1;
+                for &k in &edges[j] {
+                    if dist[k].is_none() {
+                        dist[k] = Some(d);
+                        queue.push_back(k);
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /// Returns the maximum shortest distance from a node to any other node.
+    ///
+    /// Returns `None` if some node can not be reached.
+    pub fn eccentricity(&self, node: usize) -> Option<u64> {
+        let dist = self.distance(node).ok()?;
+        dist.into_iter().map(|n| n.1).max()
+    }
+
+    /// Returns the maximum shortest distance between any two nodes.
+    ///
+    /// Returns `None` if the graph is empty or disconnected.
+    pub fn diameter(&self) -> Option<u64> {
+        let mut max = None;
+        for i in 0..self.nodes.len() {
+            let e = self.eccentricity(i)?;
+            max = Some(max.unwrap_or(0).max(e));
+        }
+        max
+    }
+
+    /// Returns the length of the shortest cycle.
+    ///
+    /// Returns `None` if the graph has no cycles.
+    /// Self edges and parallel edges are ignored, as in `cycles` and `has_cycle`.
+    pub fn girth(&self) -> Option<usize> {
+        let n = self.nodes.len();
+        let edges: Vec<Vec<usize>> = (0..n).map(|i| {
+            let mut e: Vec<usize> = self.edges_of(i).into_iter().filter(|&j| j != i).collect();
+            e.sort();
+            e.dedup();
+            e
+        }).collect();
+        let mut min: Option<usize> = None;
+        for i in 0..n {
+            let mut dist: Vec<Option<usize>> = vec![None; n];
+            let mut parent = vec![i; n];
+            dist[i] = Some(0);
+            let mut queue = std::collections::VecDeque::new();
+            queue.push_back(i);
+            while let Some(j) = queue.pop_front() {
+                let d = dist[j].unwrap();
+                for &k in &edges[j] {
+                    if k == parent[j] {continue};
+                    match dist[k] {
+                        None => {
+                            dist[k] = Some(d + 1);
+                            parent[k] = j;
+                            queue.push_back(k);
+                        }
+                        Some(e) => {
+                            let len = d + e + 1;
+                            if min.map(|m| len < m).unwrap_or(true) {min = Some(len)};
+                        }
+                    }
+                }
+            }
+        }
+        min
+    }
+
+    /// Returns all simple cycles.
+    ///
+    /// Each cycle is a list of nodes in the order they are walked,
+    /// starting at its lowest node, followed by the lower of its two neighbors.
+    /// The walk order is kept instead of sorting the nodes, since different cycles
+    /// can have the same nodes, e.g. the three cycles through all nodes of `K4`.
+    /// Cycles are sorted. Self edges and parallel edges are ignored.
+    ///
+    /// The number of cycles can grow exponentially with the number of nodes.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let n = self.nodes.len();
+        let edges: Vec<Vec<usize>> = (0..n).map(|i| {
+            let mut e: Vec<usize> = self.edges_of(i).into_iter().filter(|&j| j != i).collect();
+            e.sort();
+            e.dedup();
+            e
+        }).collect();
+        let mut res = vec![];
+        for start in 0..n {
+            // Only visit nodes greater than start, such that each cycle is found from its lowest node.
+            let mut path = vec![start];
+            let mut next = vec![0];
+            let mut on_path = vec![false; n];
+            on_path[start] = true;
+            while let Some(&j) = path.last() {
+                let k = path.len() - 1;
+                if next[k] < edges[j].len() {
+                    let e = edges[j][next[k]];
+                    next[k] += 1;
+                    if e == start && path.len() > 2 && path[1] < j {
+                        res.push(path.clone());
+                    } else if e > start && !on_path[e] {
+                        on_path[e] = true;
+                        path.push(e);
+                        next.push(0);
+                    }
+                } else {
+                    on_path[j] = false;
+                    path.pop();
+                    next.pop();
+                }
+            }
+        }
+        res.sort();
+        res
+    }
+
+    /// Returns `true` if the graph has a cycle.
+    ///
+    /// Self edges are ignored.
+    pub fn has_cycle(&self) -> bool {
+        let mut edges: Vec<(usize, usize)> = self.edges.iter()
+            .filter(|&&(a, b)| a != b)
+            .map(|&(a, b)| (a.min(b), a.max(b)))
+            .collect();
+        edges.sort();
+        edges.dedup();
+        let mut parent: Vec<usize> = (0..self.nodes.len()).collect();
+        fn root(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+        for (a, b) in edges {
+            let (a, b) = (root(&mut parent, a), root(&mut parent, b));
+            if a == b {return true};
+            parent[a] = b;
+        }
+        false
+    }
+
+    /// Returns the two parts of a bipartite graph.
+    ///
+    /// The lowest node of each connected component is put in the first part.
+    /// Unique edges are ignored.
+    /// Returns `None` if the graph has an odd cycle.
+    pub fn bipartite_parts(&self) -> Option<(Vec<usize>, Vec<usize>)> {
+        let adj = self.to_adjacency_list();
+        let mut side: Vec<Option<bool>> = vec![None; adj.len()];
+        for i in 0..adj.len() {
+            if side[i].is_some() {continue};
+            side[i] = Some(false);
+            let mut stack = vec![i];
+            while let Some(j) = stack.pop() {
+                let s = side[j].unwrap();
+                for &k in &adj[j] {
+                    match side[k] {
+                        None => {
+                            side[k] = Some(!s);
+                            stack.push(k);
+                        }
+                        Some(t) if t == s => return None,
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+        Some((0..adj.len()).partition(|&i| side[i] == Some(false)))
+    }
+
+    /// Returns an iterator over nodes in breadth first order from a start node.
+    ///
+    /// The iterator owns an adjacency list built on creation,
+    /// so it does not borrow the graph.
+    pub fn bfs(&self, start: usize) -> BfsIter {
+        BfsIter {inner: self.bfs_with_depth(start)}
+    }
+
+    /// Returns an iterator over nodes and their depth
+    /// in breadth first order from a start node.
+    ///
+    /// The depth is the shortest distance to the start node.
+    /// The iterator owns an adjacency list built on creation,
+    /// so it does not borrow the graph.
+    pub fn bfs_with_depth(&self, start: usize) -> BfsDepthIter {
+        let mut visited = vec![false; self.nodes.len()];
+        visited[start] = true;
+        BfsDepthIter {
+            adj: self.to_adjacency_list(),
+            visited,
+            queue: vec![(start, 0)].into(),
+        }
+    }
+
+    /// Returns an iterator over nodes in depth first order from a start node.
+    ///
+    /// The iterator owns an adjacency list built on creation,
+    /// so it does not borrow the graph.
+    pub fn dfs(&self, start: usize) -> DfsIter {
+        DfsIter {
+            adj: self.to_adjacency_list(),
+            visited: vec![false; self.nodes.len()],
+            stack: vec![start],
+        }
+    }
+
+    /// Returns the connected components of the graph.
+    ///
+    /// Each component is a sorted list of nodes.
+    /// Components are ordered by their lowest node.
+    pub fn connected_components(&self) -> Vec<Vec<usize>> {
+        let n = self.nodes.len();
+        let edges: Vec<Vec<usize>> = (0..n).map(|i| self.edges_of(i)).collect();
+        let mut visited = vec![false; n];
+        let mut res = vec![];
+        for i in 0..n {
+            if visited[i] {continue};
+            visited[i] = true;
+            let mut component = vec![i];
+            let mut k = 0;
+            while k < component.len() {
+                for &j in &edges[component[k]] {
+                    if !visited[j] {
+                        visited[j] = true;
+                        component.push(j);
+                    }
+                }
+                k += 1;
+            }
+            component.sort();
+            res.push(component);
+        }
+        res
+    }
+
+    /// Returns `true` if all nodes are connected.
+    pub fn is_connected(&self) -> bool {
+        self.connected_components().len() <= 1
+    }
+
     /// Returns avatar distances of nodes from a core node.
     ///
     /// The avatar distance is greater or equal to shortest distance.
@@ -416,21 +1129,38 @@ impl Graph {
         dist
     }
 
+    /// Returns the number of nodes at each avatar level from a core node.
+    ///
+    /// Nodes with the same avatar distance belong to the same level.
+    /// Levels are numbered in order of increasing avatar distance,
+    /// such that level `0` is the core itself.
+    pub fn avatar_distance_histogram(&self, core: usize) -> Vec<(u64, usize)> {
+        let levels = self.avatar_level(core);
+        let n = levels.iter().map(|n| n.1 + 1).max().unwrap_or(0);
+        (0..n).map(|k| (k, levels.iter().filter(|n| n.1 == k).count())).collect()
+    }
+
+    /// Returns avatar levels of nodes from a core node.
+    ///
+    /// Nodes with the same avatar distance belong to the same level.
+    /// Levels are numbered in order of increasing avatar distance,
+    /// such that level `0` is the core itself.
+    pub fn avatar_level(&self, core: usize) -> Vec<(usize, u64)> {
+        let dist = self.avatar_distance(core);
+        let mut values: Vec<u64> = dist.iter().map(|n| n.1).collect();
+        values.sort();
+        values.dedup();
+        dist.into_iter().map(|(i, d)| (i, values.binary_search(&d).unwrap() as u64)).collect()
+    }
+
+    /// Returns the nodes at avatar level `k` from a core node.
+    pub fn k_avatars(&self, core: usize, k: u64) -> Vec<usize> {
+        self.avatar_level(core).into_iter().filter(|n| n.1 == k).map(|n| n.0).collect()
+    }
+
     /// Returns a list of maximum avatars and their maximum avatar distance.
     pub fn max_avatars(&self, ind: usize) -> (u64, Vec<usize>) {
-        let dist = self.avatar_distance(ind);
-        let mut max = 0;
-        let mut avatars = vec![];
-        for &(a, n) in &dist {
-            if n > max {
-                avatars.clear();
-                avatars.push(a);
-                max = n;
-            } else if n == max {
-                avatars.push(a);
-            }
-        }
-        (max, avatars)
+        max_avatars_of(&self.avatar_distance(ind))
     }
 
     /// Returns the nodes that are contractible.
@@ -496,7 +1226,46 @@ impl Graph {
         res
     }
 
-    /// Swaps two nodes.
+    /// Returns the number of semi-contractible nodes relative to a core.
+    pub fn semi_contractible(&self, ind: usize) -> usize {
+        self.semi_contractibles_of(ind).len()
+    }
+
+    /// Returns the semi-contractible nodes relative to a core.
+    ///
+    /// A node is semi-contractible if it has only one child with shorter avatar distance,
+    /// but more than one child with shorter or equal shortest distance.
+    /// The core is not counted as a child.
+    pub fn semi_contractibles_of(&self, ind: usize) -> Vec<usize> {
+        let dist = match self.distance(ind) {
+            Ok(x) => x,
+            Err(x) => x,
+        };
+        let avatar_dist = self.avatar_distance(ind);
+        let mut res = vec![];
+        for i in 0..dist.len() {
+            let j = dist[i].0;
+            let n = dist[i].1;
+            let a = avatar_dist[i].1;
+            let edges = self.edges_of(j);
+            // Count children by shortest distance and by avatar distance.
+            let mut count = 0;
+            let mut avatar_count = 0;
+            for &e in &edges {
+                let k = dist.binary_search_by(|n| n.0.cmp(&e)).unwrap();
+                let m = dist[k].1;
+                if m != 0 && m <= n {count += 1};
+                let m = avatar_dist[k].1;
+                if m != 0 && m < a {avatar_count += 1};
+            }
+            if avatar_count == 1 && count > 1 {
+                res.push(j);
+            }
+        }
+        res
+    }
+
+    /// Swaps two nodes.
     pub fn swap(&mut self, a: usize, b: usize) {
         // Swap edges.
         for i in 0..self.edges.len() {
@@ -565,7 +1334,11 @@ impl Graph {
 
     /// Returns `true` if a graph has correct avatar connectivity.
     pub fn avatar_connectivity(&self, ind: usize) -> bool {
-        let dist = self.avatar_distance(ind);
+        self.avatar_connectivity_with(&self.avatar_distance(ind))
+    }
+
+    /// Same as `avatar_connectivity`, using precomputed avatar distances.
+    fn avatar_connectivity_with(&self, dist: &[(usize, u64)]) -> bool {
         for i in 0..dist.len() {
             let j = dist[i].0;
             let n = dist[i].1;
@@ -587,7 +1360,12 @@ impl Graph {
 
     /// Returns a list of nodes which have wrong avatar connectivity.
     pub fn avatar_connectivity_failures_of(&self, ind: usize) -> Vec<usize> {
-        let mut dist = self.avatar_distance(ind);
+        self.avatar_connectivity_failures_with(ind, &self.avatar_distance(ind))
+    }
+
+    /// Same as `avatar_connectivity_failures_of`, using precomputed avatar distances.
+    fn avatar_connectivity_failures_with(&self, ind: usize, dist: &[(usize, u64)]) -> Vec<usize> {
+        let mut dist = dist.to_vec();
         dist.sort_by_key(|n| n.1);
         let mut res = vec![];
         for i in 0..dist.len() {
@@ -620,18 +1398,78 @@ impl Graph {
 
     /// Returns `true` if the graph is an Avatar Graph seen from a core.
     pub fn is_avatar_graph(&self, ind: usize) -> bool {
+        self.validate(ind).is_ok()
+    }
+
+    /// Checks whether the graph is an Avatar Graph seen from a core.
+    ///
+    /// Returns the first violation found, in the same order as `is_avatar_graph`.
+    pub fn validate(&self, ind: usize) -> Result<(), AvatarGraphError> {
+        self.validate_with(ind, None)
+    }
+
+    /// Same as `validate`, using avatar distances from a cache.
+    ///
+    /// Avatar distances are computed if the cache entry is invalidated.
+    /// The cache must be up to date with the graph (see `AvatarCache`).
+    pub fn validate_cached(&self, ind: usize, cache: &AvatarCache) -> Result<(), AvatarGraphError> {
+        self.validate_with(ind, cache.dist.get(ind).and_then(|dist| dist.as_deref()))
+    }
+
+    /// Same as `is_avatar_graph`, using avatar distances from a cache.
+    ///
+    /// Avatar distances are computed if the cache entry is invalidated.
+    /// The cache must be up to date with the graph (see `AvatarCache`).
+    pub fn is_avatar_graph_cached(&self, ind: usize, cache: &AvatarCache) -> bool {
+        self.validate_cached(ind, cache).is_ok()
+    }
+
+    /// Checks an Avatar Graph using precomputed avatar distances,
+    /// or computes them if `avatar_dist` is `None`.
+    fn validate_with(&self, ind: usize, avatar_dist: Option<&[(usize, u64)]>) -> Result<(), AvatarGraphError> {
+        use AvatarGraphError::*;
+
         // There can be no contractible nodes.
-        if self.contractible(ind) != 0 {return false};
+        let contractibles = self.contractibles_of(ind);
+        if !contractibles.is_empty() {return Err(Contractible(contractibles))};
         // The whole graph must be connected.
-        if self.distance(ind).is_err() {return false};
+        if let Err(dist) = self.distance(ind) {
+            let nodes = (0..self.nodes.len())
+                .filter(|&i| dist.binary_search_by(|n| n.0.cmp(&i)).is_err())
+                .collect();
+            return Err(Disconnected(nodes));
+        }
+        let computed;
+        let avatar_dist = match avatar_dist {
+            Some(avatar_dist) => avatar_dist,
+            None => {
+                computed = self.avatar_distance(ind);
+                &computed
+            }
+        };
         // There must exist only one max avatar.
-        let max_avatars = self.max_avatars(ind);
-        if max_avatars.1.len() != 1 {return false};
+        let max_avatars = max_avatars_of(avatar_dist);
+        if max_avatars.1.len() != 1 {return Err(NonUniqueMaxAvatar(max_avatars.1))};
         // All nodes must be reachable when walking from max avatar to the core.
-        if !self.all_reachable_along(max_avatars.1[0], ind) {return false};
+        let along = self.along(max_avatars.1[0], ind).unwrap_or_default();
+        if along.len() != self.nodes.len() {
+            let nodes = (0..self.nodes.len())
+                .filter(|i| along.binary_search(i).is_err())
+                .collect();
+            return Err(NotUniversallyReachable(nodes));
+        }
         // Nodes must follow rules for avatar connectivity.
-        if !self.avatar_connectivity(ind) {return false};
-        true
+        if !self.avatar_connectivity_with(avatar_dist) {
+            let mut nodes = self.avatar_connectivity_failures_with(ind, avatar_dist);
+            nodes.sort();
+            return Err(AvatarConnectivityViolation(nodes));
+        }
+        Ok(())
+    }
+
+    /// Computes avatar distances from every node.
+    pub fn build_cache(&self) -> AvatarCache {
+        AvatarCache::build(self)
     }
 
     /// Marks all nodes as core that can be a core,
@@ -647,391 +1485,3063 @@ impl Graph {
             }
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn simple_graph() {
-        let mut g = Graph::new();
-        let a = g.add_node(Node::new(true));
-        let b = g.add_node(Node::new(false));
-        g.add_edge(a, b);
-        assert_eq!(g.nodes.len(), 2);
-        assert_eq!(g.edges.len(), 1);
-        assert_eq!(g.cores(), 1);
-        assert_eq!(g.non_cores(), 1);
-        assert_eq!(g.edges_of(a), vec![b]);
-        assert_eq!(g.edges_of(b), vec![a]);
-        assert_eq!(g.self_edges(), 0);
-        assert_eq!(g.matrix(), vec![
-            vec![0, 1],
-            vec![0, 0]
-        ]);
-        assert_eq!(g.unique_edges(), 0);
+    /// Returns `true` if all unique edges point to the max avatar of their node.
+    ///
+    /// A unique edge is only valid from a node where the graph is an Avatar Graph,
+    /// which is how `corify` assigns unique edges.
+    pub fn unique_edges_valid(&self) -> bool {
+        (0..self.nodes.len()).all(|i| match self.nodes[i].uniq {
+            None => true,
+            Some(j) => j < self.nodes.len() &&
+                self.is_avatar_graph(i) && self.max_avatars(i).1[0] == j,
+        })
     }
 
-    #[test]
-    fn remove_self_edges() {
-        let mut g = Graph::new();
-        let a = g.add_node(Node::new(true));
-        g.add_edge(a, a);
-        assert_eq!(g.self_edges(), 1);
-        g.remove_self_edges();
-        assert_eq!(g.self_edges(), 0);
-        assert_eq!(g.matrix(), vec![
-            vec![0]
-        ]);
-        assert_eq!(g.unique_edges(), 0);
+    /// Points existing unique edges to the max avatar of their node.
+    ///
+    /// Unique edges from nodes where the graph is not an Avatar Graph are removed.
+    /// Cores are not changed, and no new unique edges are added.
+    pub fn repair_unique_edges(&mut self) {
+        for i in 0..self.nodes.len() {
+            if self.nodes[i].uniq.is_none() {continue};
+            self.nodes[i].uniq = if self.is_avatar_graph(i) {
+                Some(self.max_avatars(i).1[0])
+            } else {
+                None
+            };
+        }
     }
 
-    #[test]
-    fn unique_edge() {
-        let mut g = Graph::new();
-        let a = g.add_node(Node::new(true));
-        let b = g.add_node(Node::new(false));
-        assert_eq!(g.matrix(), vec![
-            vec![0, 0],
-            vec![0, 0]
-        ]);
-        assert_eq!(g.unique_edges(), 0);
-        g.nodes[a].uniq = Some(b);
-        assert_eq!(g.unique_edges(), 1);
-        assert_eq!(g.matrix(), vec![
-            vec![0, 2],
-            vec![0, 0]
-        ]);
-        g.add_edge(a, b);
-        assert_eq!(g.matrix(), vec![
-            vec![0, 3],
-            vec![0, 0]
-        ]);
-        assert_eq!(g.unique_edges(), 1);
+    /// Same as `corify`, but checks nodes in parallel.
+    #[cfg(feature = "rayon")]
+    pub fn corify_parallel(&mut self)
+        where L: Sync
+    {
+        use rayon::prelude::*;
+
+        let uniq: Vec<Option<usize>> = (0..self.nodes.len()).into_par_iter().map(|i| {
+            if self.is_avatar_graph(i) {Some(self.max_avatars(i).1[0])} else {None}
+        }).collect();
+        for (node, uniq) in self.nodes.iter_mut().zip(uniq) {
+            node.core = uniq.is_some();
+            node.uniq = uniq;
+        }
     }
 
-    #[test]
-    fn self_unique_edge() {
-        let mut g = Graph::new();
-        let a = g.add_node(Node::new(true));
-        assert_eq!(g.self_unique_edges(), 0);
-        g.nodes[a].uniq = Some(a);
-        assert_eq!(g.self_unique_edges(), 1);
-        g.remove_self_unique_edges();
-        assert_eq!(g.self_unique_edges(), 0);
+    /// Returns the Shannon entropy of the avatar distance distribution.
+    ///
+    /// For every valid core, the fraction of nodes at each avatar distance is computed.
+    /// These fractions are averaged over all valid cores,
+    /// and the entropy is `-sum_d p_d * log2(p_d)`.
+    ///
+    /// Returns `0.0` if there are no valid cores.
+    pub fn avatar_graph_entropy(&self) -> f64 {
+        let mut dist_sum: Vec<(u64, f64)> = vec![];
+        let mut cores = 0;
+        for i in 0..self.nodes.len() {
+            if !self.is_avatar_graph(i) {continue};
+            cores += 1;
+            let dist = self.avatar_distance(i);
+            let n = dist.len() as f64;
+            for &(_, d) in &dist {
+                match dist_sum.binary_search_by(|p| p.0.cmp(&d)) {
+                    Ok(k) => dist_sum[k].1 += 1.0 / n,
+                    Err(k) => dist_sum.insert(k, (d, 1.0 / n)),
+                }
+            }
+        }
+        if cores == 0 {return 0.0};
+        let mut entropy = 0.0;
+        for &(_, p) in &dist_sum {
+            let p = p / cores as f64;
+            entropy -= p * p.log2();
+        }
+        entropy
     }
 
-    #[test]
-    fn order() {
-        let mut g = Graph::new();
-        let a = g.add_node(Node::new(true));
-        let b = g.add_node(Node::new(false));
-        assert_eq!(g.distance(a), Err(vec![(a, 0)]));
-        assert_eq!(g.distance(b), Err(vec![(b, 0)]));
-        g.add_edge(a, b);
-        assert_eq!(g.distance(a), Ok(vec![(a, 0), (b, 1)]));
-        assert_eq!(g.distance(b), Ok(vec![(a, 1), (b, 0)]));
+    /// Returns a score between `0.0` and `1.0` of how evenly nodes are
+    /// distributed across avatar levels relative to a core.
+    ///
+    /// Nodes with the same avatar distance belong to the same level.
+    /// The score is `1.0` minus the coefficient of variation of level sizes,
+    /// normalized by its maximum value for the number of levels.
+    /// An equal number of nodes at each level gives `1.0`.
+    pub fn avatar_balance(&self, core: usize) -> f64 {
+        let dist = self.avatar_distance(core);
+        let mut levels: Vec<(u64, usize)> = vec![];
+        for &(_, d) in &dist {
+            match levels.binary_search_by(|n| n.0.cmp(&d)) {
+                Ok(k) => levels[k].1 += 1,
+                Err(k) => levels.insert(k, (d, 1)),
+            }
+        }
+        if levels.len() < 2 {return 1.0};
+        let k = levels.len() as f64;
+        let mean = dist.len() as f64 / k;
+        let var = levels.iter().map(|n| (n.1 as f64 - mean).powi(2)).sum::<f64>() / k;
+        let cv = var.sqrt() / mean;
+        1.0 - cv / (k - 1.0).sqrt()
     }
 
-    #[test]
-    fn max_avatars() {
-        let mut g = Graph::new();
-        let a = g.add_node(Node::new(true));
-        let b = g.add_node(Node::new(false));
-        let c = g.add_node(Node::new(false));
-        let d = g.add_node(Node::new(false));
-        g.add_edge(a, b);
-        g.add_edge(a, c);
-        g.add_edge(b, d);
-        g.add_edge(c, d);
-        assert_eq!(g.max_avatars(a), (2, vec![d]));
+    /// Returns a 1-dimensional Weisfeiler-Lehman hash of the graph.
+    ///
+    /// Each node starts with its degree as color.
+    /// For each iteration, the color of a node is replaced by a hash
+    /// of its color and the sorted colors of its neighbors.
+    ///
+    /// Isomorphic graphs always have the same hash.
+    /// Non-isomorphic graphs might have the same hash.
+    /// The hash is the same across runs and platforms.
+    pub fn wl_hash(&self, iterations: usize) -> u64 {
+        let n = self.nodes.len();
+        let edges: Vec<Vec<usize>> = (0..n).map(|i| self.edges_of(i)).collect();
+        let mut colors: Vec<u64> = edges.iter().map(|e| e.len() as u64).collect();
+        for _ in 0..iterations {
+            let mut new_colors = Vec::with_capacity(n);
+            for i in 0..n {
+                let mut neighbors: Vec<u64> = edges[i].iter().map(|&j| colors[j]).collect();
+                neighbors.sort();
+                let mut hasher = StableHasher::new();
+                colors[i].hash(&mut hasher);
+                neighbors.hash(&mut hasher);
+                new_colors.push(hasher.finish());
+            }
+            colors = new_colors;
+        }
+        colors.sort();
+        let mut hasher = StableHasher::new();
+        colors.hash(&mut hasher);
+        hasher.finish()
     }
 
-    #[test]
-    fn avatar3() {
-        //      a ----- b
-        //      |       |  \
-        //      |       |    e
-        //      |       |  /
-        //      c ----- d
-        let mut g = Graph::new();
-        let a = g.add_node(Node::new(true));
-        let b = g.add_node(Node::new(false));
-        let c = g.add_node(Node::new(false));
-        let d = g.add_node(Node::new(false));
-        let e = g.add_node(Node::new(false));
-        g.add_edge(a, b);
-        g.add_edge(a, c);
-        g.add_edge(b, d);
-        g.add_edge(c, d);
-        g.add_edge(b, e);
-        g.add_edge(d, e);
-        assert_eq!(g.avatar_distance(a), vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 3)]);
+    /// Returns a 2-dimensional Weisfeiler-Lehman hash of the graph.
+    ///
+    /// Each pair of nodes starts with a color given by whether they are
+    /// equal or adjacent. The color of a pair `(i, j)` is replaced by a hash
+    /// of its color and the sorted pairs of colors of `(i, k)` and `(k, j)`
+    /// for all nodes `k`, until the number of colors stops increasing.
+    ///
+    /// This distinguishes more graphs than `wl_hash`, but is more expensive.
+    /// The hash is the same across runs and platforms.
+    pub fn wl2_hash(&self) -> u64 {
+        let n = self.nodes.len();
+        let mut colors = vec![vec![0; n]; n];
+        for (i, row) in colors.iter_mut().enumerate() {
+            row[i] = 1;
+        }
+        for &(a, b) in &self.edges {
+            colors[a][b] |= 2;
+            colors[b][a] |= 2;
+        }
+        let count_colors = |colors: &Vec<Vec<u64>>| {
+            let mut all: Vec<u64> = colors.iter().flat_map(|row| row.iter().cloned()).collect();
+            all.sort();
+            all.dedup();
+            all.len()
+        };
+        let mut count = count_colors(&colors);
+        loop {
+            let mut new_colors = vec![vec![0; n]; n];
+            for i in 0..n {
+                for j in 0..n {
+                    let mut pairs: Vec<(u64, u64)> = (0..n).map(|k| (colors[i][k], colors[k][j])).collect();
+                    pairs.sort();
+                    let mut hasher = StableHasher::new();
+                    colors[i][j].hash(&mut hasher);
+                    pairs.hash(&mut hasher);
+                    new_colors[i][j] = hasher.finish();
+                }
+            }
+            colors = new_colors;
+            let new_count = count_colors(&colors);
+            if new_count == count {break};
+            count = new_count;
+        }
+        let mut all: Vec<u64> = colors.into_iter().flatten().collect();
+        all.sort();
+        let mut hasher = StableHasher::new();
+        all.hash(&mut hasher);
+        hasher.finish()
     }
 
-    #[test]
-    fn contractible() {
-        let mut g = Graph::new();
-        let a = g.add_node(Node::new(true));
-        let b = g.add_node(Node::new(false));
-        let c = g.add_node(Node::new(false));
-        g.add_edge(a, b);
-        g.add_edge(b, c);
-        assert_eq!(g.contractible(a), 1);
+    /// Returns a structural hash that does not depend on the order of nodes.
+    ///
+    /// Combines degrees, triangle counts, an approximation of the largest
+    /// eigenvalue of the adjacency matrix, the number of cores and
+    /// the sorted max avatar levels of the cores.
+    /// Isomorphic graphs have the same fingerprint,
+    /// while non-isomorphic graphs have different fingerprints with high probability.
+    /// Core flags, unique edges and labels are ignored.
+    ///
+    /// Whether a node is a core can depend on the order of nodes,
+    /// so all terms are computed on the graph in normal form (see `normalize`).
+    /// This is not linear time: normalization is exponential in the worst case,
+    /// and every node is checked with `is_avatar_graph`.
+    ///
+    /// The result is the same across runs and platforms.
+    pub fn fingerprint(&self) -> u64 {
+        let n = self.nodes.len();
+        let g = Graph {nodes: vec![Node::new(false); n], edges: self.edges.clone()}.normalize();
+        let edges: Vec<Vec<usize>> = (0..n).map(|i| g.edges_of(i)).collect();
+        let mut degrees: Vec<usize> = edges.iter().map(|e| e.len()).collect();
+        degrees.sort();
+        let mut triangles: Vec<usize> = (0..n).map(|i| {
+            let mut count = 0;
+            for &j in &edges[i] {
+                for &k in &edges[i] {
+                    if j < k && edges[j].contains(&k) {count += 1}
+                }
+            }
+            count
+        }).collect();
+        triangles.sort();
+
+        // Power iteration with shifted matrix `A + I` to converge for bipartite graphs.
+        let mut v = vec![1.0; n];
+        let mut eigenvalue = 0.0;
+        for _ in 0..100 {
+            let w: Vec<f64> = (0..n).map(|i| v[i] + edges[i].iter().map(|&j| v[j]).sum::<f64>()).collect();
+            let norm = w.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm == 0.0 {break};
+            eigenvalue = v.iter().zip(&w).map(|(a, b)| a * b).sum::<f64>() /
+                v.iter().map(|x| x * x).sum::<f64>() - 1.0;
+            v = w.into_iter().map(|x| x / norm).collect();
+        }
+
+        let mut levels: Vec<usize> = (0..n)
+            .filter(|&i| g.is_avatar_graph(i))
+            .map(|i| g.avatar_distance_histogram(i).len())
+            .collect();
+        levels.sort();
+
+        let mut hasher = StableHasher::new();
+        n.hash(&mut hasher);
+        degrees.hash(&mut hasher);
+        triangles.hash(&mut hasher);
+        ((eigenvalue * 1e6).round() as i64).hash(&mut hasher);
+        levels.len().hash(&mut hasher);
+        levels.hash(&mut hasher);
+        hasher.finish()
     }
 
-    #[test]
-    fn swap() {
-        let mut g = Graph::new();
-        let a = g.add_node(Node::new(true));
-        let b = g.add_node(Node::new(false));
-        let c = g.add_node(Node::new(false));
-        g.add_edge(a, b);
-        g.add_edge(a, c);
-        assert_eq!(g.edges, vec![(0, 1), (0, 2)]);
-        g.swap(a, b);
-        assert_eq!(g.edges, vec![(0, 1), (1, 2)]);
+    /// Ranks nodes by how much they contribute to the avatar structure.
+    ///
+    /// The importance of a node is the decrease in fill ratio after corify,
+    /// when the node is removed from the graph.
+    /// Nodes are sorted by decreasing importance.
+    pub fn node_importance(&self) -> Vec<(usize, f64)> {
+        let n = self.nodes.len();
+        let mut g = self.map_labels(|_| ());
+        g.corify();
+        let fill_ratio = g.fill_ratio();
+        let mut res = vec![];
+        for i in 0..n {
+            let mut h = self.map_labels(|_| ());
+            h.remove_node(i);
+            h.corify();
+            res.push((i, fill_ratio - h.fill_ratio()));
+        }
+        res.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+        res
     }
 
-    #[test]
-    fn avatar_graph() {
-        let mut g = Graph::new();
-        let a = g.add_node(Node::new(true));
-        let b = g.add_node(Node::new(false));
-        assert_eq!(g.is_avatar_graph(a), false);
-        g.add_edge(a, b);
-        assert_eq!(g.is_avatar_graph(a), true);
-        assert_eq!(g.is_avatar_graph(b), true);
-        let c = g.add_node(Node::new(false));
-        assert_eq!(g.is_avatar_graph(a), false);
-        g.add_edge(a, c);
-        assert_eq!(g.is_avatar_graph(a), false);
-        let d = g.add_node(Node::new(false));
-        assert_eq!(g.is_avatar_graph(a), false);
-        g.add_edge(c, d);
-        assert_eq!(g.is_avatar_graph(a), false);
-        g.add_edge(b, d);
-        assert_eq!(g.is_avatar_graph(a), true);
+    /// Returns an iterator over all connected induced subgraphs.
+    ///
+    /// Each subgraph is normalized.
+    ///
+    /// Subsets of nodes are visited lazily, so there is no limit on the number of nodes.
+    /// The number of subsets grows exponentially with the number of nodes,
+    /// so this is only practical to exhaust for small graphs.
+    pub fn connected_induced_subgraphs(&self) -> impl Iterator<Item = Graph<L>> + '_
+        where L: Clone + Ord
+    {
+        let n = self.nodes.len();
+        let mut mask = vec![false; n];
+        std::iter::from_fn(move || {
+            while next_subset(&mut mask) {
+                let nodes: Vec<usize> = (0..n).filter(|&i| mask[i]).collect();
+                let g = self.induced_subgraph(&nodes);
+                if g.is_connected() {return Some(g.normalize())};
+            }
+            None
+        })
     }
 
-    #[test]
-    fn corify() {
-        let mut g = Graph::new();
-        let a = g.add_node(Node::new(false));
-        let b = g.add_node(Node::new(false));
-        let c = g.add_node(Node::new(false));
-        let d = g.add_node(Node::new(false));
+    /// Returns the ways to extend the graph with a new max avatar.
+    ///
+    /// Tries connecting a new node to every non-empty subset of nodes.
+    /// An extension is kept when the graph is an Avatar Graph seen from `core`
+    /// and the new node is the unique max avatar.
+    /// Each extension is the index of the new node and the nodes it connects to.
+    ///
+    /// The number of subsets grows exponentially with the number of nodes,
+    /// so this is only practical for small graphs.
+    pub fn avatar_extensions(&self, core: usize) -> Vec<(usize, Vec<usize>)> {
+        let n = self.nodes.len();
+        let mut res = vec![];
+        let mut mask = vec![false; n];
+        while next_subset(&mut mask) {
+            let nodes: Vec<usize> = (0..n).filter(|&i| mask[i]).collect();
+            let mut g = self.map_labels(|_| ());
+            let id = g.add_node(Node::new(false));
+            for &i in &nodes {
+                g.add_edge(i, id);
+            }
+            if g.is_avatar_graph(core) && g.max_avatars(core).1 == [id] {
+                res.push((id, nodes));
+            }
+        }
+        res
+    }
+
+    /// Returns the size of the largest set of mutually non-adjacent nodes.
+    ///
+    /// A node with a self edge is adjacent to itself and is never in the set.
+    /// Uses an exact branch-and-bound search, which has exponential worst case.
+    /// Returns `None` for graphs with more than 20 nodes.
+    pub fn independence_number(&self) -> Option<usize> {
+        if self.nodes.len() > 20 {return None};
+        Some(self.maximum_independent_set().len())
+    }
+
+    /// Returns a smallest set of nodes such that every edge has an end in the set.
+    ///
+    /// This is the complement of a maximum independent set.
+    /// Uses an exact branch-and-bound search, which has exponential worst case.
+    ///
+    /// For graphs with more than 20 nodes, the result is not exact:
+    /// it contains both ends of a maximal matching, which is at most twice
+    /// the size of a minimum vertex cover.
+    pub fn minimum_vertex_cover(&self) -> Vec<usize> {
+        let n = self.nodes.len();
+        if n > 20 {
+            let mut covered = vec![false; n];
+            for &(a, b) in &self.edges {
+                if !covered[a] && !covered[b] {
+                    covered[a] = true;
+                    covered[b] = true;
+                }
+            }
+            return (0..n).filter(|&i| covered[i]).collect();
+        }
+        let independent = self.maximum_independent_set();
+        (0..n).filter(|i| independent.binary_search(i).is_err()).collect()
+    }
+
+    /// Returns a largest set of mutually non-adjacent nodes.
+    ///
+    /// Nodes with self edges are never included.
+    fn maximum_independent_set(&self) -> Vec<usize> {
+        let n = self.nodes.len();
+        let mut adj = vec![vec![false; n]; n];
+        for &(a, b) in &self.edges {
+            adj[a][b] = true;
+            adj[b][a] = true;
+        }
+        let candidates = (0..n).filter(|&i| !adj[i][i]).collect();
+        let mut best = vec![];
+        independent_set_search(&adj, candidates, &mut vec![], &mut best);
+        best.sort();
+        best
+    }
+
+    /// Returns `true` if the graph can be drawn in the plane without crossing edges.
+    ///
+    /// Each biconnected component is tested using the algorithm of
+    /// Demoucron, Malgrange and Pertuiset. Self edges are ignored.
+    pub fn is_planar(&self) -> bool {
+        let n = self.nodes.len();
+        let mut adj: Vec<Vec<usize>> = vec![vec![]; n];
+        for &(a, b) in &self.edges {
+            if a == b {continue};
+            adj[a].push(b);
+            adj[b].push(a);
+        }
+        for list in &mut adj {
+            list.sort();
+            list.dedup();
+        }
+        let m = adj.iter().map(|list| list.len()).sum::<usize>() / 2;
+        if n >= 3 && m > 3 * n - 6 {return false};
+
+        let mut disc = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut time = 0;
+        let mut stack = vec![];
+        let mut blocks = vec![];
+        for i in 0..n {
+            if disc[i] == usize::MAX {
+                biconnected_visit(i, n, &adj, &mut disc, &mut low, &mut time, &mut stack, &mut blocks);
+            }
+        }
+        blocks.iter().all(|block| planar_block(block))
+    }
+
+    /// Returns `true` if the graph can be drawn in the plane without crossing edges,
+    /// such that all nodes are on the outer face.
+    ///
+    /// A graph is outerplanar if and only if it is planar after adding
+    /// a new node connected to all other nodes.
+    pub fn is_outerplanar(&self) -> bool {
+        let n = self.nodes.len();
+        let mut g = Graph {
+            nodes: vec![Node::new(false); n + 1],
+            edges: self.edges.clone(),
+        };
+        for i in 0..n {
+            g.edges.push((i, n));
+        }
+        g.is_planar()
+    }
+
+    /// Returns the number of faces in a planar drawing, including the outer face.
+    ///
+    /// Uses Euler's formula `V - E + F = 2`.
+    /// Returns `None` if the graph is empty, disconnected or not planar.
+    pub fn face_count(&self) -> Option<usize> {
+        if self.nodes.is_empty() || !self.is_connected() || !self.is_planar() {return None};
+        let mut edges: Vec<(usize, usize)> = self.edges.iter().cloned().filter(|&(a, b)| a != b).collect();
+        edges.sort();
+        edges.dedup();
+        Some(2 + edges.len() - self.nodes.len())
+    }
+
+    /// Returns the bandwidth of the graph.
+    ///
+    /// The bandwidth is the minimum over all orderings of nodes,
+    /// of the maximum difference in position between adjacent nodes.
+    ///
+    /// The exact value is computed for graphs with at most 12 nodes,
+    /// by searching upwards from lower bounds given by degrees, distances and
+    /// the number of edges, until the upper bound is reached.
+    /// For larger graphs, an upper bound is computed from a
+    /// breadth first search ordering (Cuthill-McKee).
+    pub fn bandwidth(&self) -> usize {
+        let n = self.nodes.len();
+        let mut adj = vec![vec![false; n]; n];
+        for &(a, b) in &self.edges {
+            if a == b {continue};
+            adj[a][b] = true;
+            adj[b][a] = true;
+        }
+        let degrees: Vec<usize> = adj.iter().map(|row| row.iter().filter(|&&x| x).count()).collect();
+
+        // Breadth first search from nodes of low degree, visiting neighbors by degree.
+        let mut order = vec![];
+        let mut visited = vec![false; n];
+        let mut starts: Vec<usize> = (0..n).collect();
+        starts.sort_by_key(|&i| degrees[i]);
+        for &start in &starts {
+            if visited[start] {continue};
+            visited[start] = true;
+            let first = order.len();
+            order.push(start);
+            let mut i = first;
+            while i < order.len() {
+                let mut neighbors: Vec<usize> = (0..n).filter(|&j| adj[order[i]][j] && !visited[j]).collect();
+                neighbors.sort_by_key(|&j| degrees[j]);
+                for j in neighbors {
+                    visited[j] = true;
+                    order.push(j);
+                }
+                i += 1;
+            }
+        }
+        let mut pos = vec![0; n];
+        for (i, &j) in order.iter().enumerate() {
+            pos[j] = i;
+        }
+        let mut upper = 0;
+        for a in 0..n {
+            for b in 0..n {
+                if adj[a][b] {upper = upper.max(pos[a].max(pos[b]) - pos[a].min(pos[b]))}
+            }
+        }
+        if n > 12 {return upper};
+
+        // A node has at most `2b` neighbors and at most `2db` nodes within distance `d`.
+        let mut lower = degrees.iter().cloned().max().unwrap_or(0).div_ceil(2);
+        let mut component = vec![usize::MAX; n];
+        for v in 0..n {
+            let mut dist = vec![usize::MAX; n];
+            dist[v] = 0;
+            let mut queue = vec![v];
+            let mut i = 0;
+            while i < queue.len() {
+                let a = queue[i];
+                for j in 0..n {
+                    if adj[a][j] && dist[j] == usize::MAX {
+                        dist[j] = dist[a] + 1;
+                        queue.push(j);
+                    }
+                }
+                i += 1;
+            }
+            // Nodes are in order of distance, so the first `k + 1` are within distance `dist[queue[k]]`.
+            for (k, &j) in queue.iter().enumerate().skip(1) {
+                lower = lower.max(k.div_ceil(2 * dist[j]));
+            }
+            if component[v] == usize::MAX {
+                for &j in &queue {component[j] = v}
+            }
+        }
+        // A component with `k` nodes and bandwidth `b` has at most `kb - b(b + 1)/2` edges.
+        for c in 0..n {
+            let k = component.iter().filter(|&&x| x == c).count();
+            let m = (0..n).filter(|&a| component[a] == c)
+                .map(|a| degrees[a]).sum::<usize>() / 2;
+            let mut b = 0;
+            while k * b < m + b * (b + 1) / 2 {b += 1}
+            lower = lower.max(b);
+        }
+        for b in lower..upper {
+            if bandwidth_search(&adj, b, &mut vec![], &mut vec![None; n]) {return b};
+        }
+        upper
+    }
+
+    /// Returns the coefficients of the chromatic polynomial `P(G, k)`,
+    /// which counts the number of proper colorings with `k` colors.
+    ///
+    /// Coefficient at index `i` belongs to `k^i`.
+    /// Uses deletion-contraction, which takes exponential time.
+    pub fn chromatic_polynomial(&self) -> Vec<i64> {
+        let n = self.nodes.len();
+        if self.self_edges() > 0 {return vec![0; n + 1]};
+        let mut edges = self.edges.clone();
+        edges.sort();
+        edges.dedup();
+        chromatic_polynomial(n, edges)
+    }
+
+    /// Returns `Some(n)` if the graph is isomorphic to an n-dimensional hypercube.
+    ///
+    /// Assigns binary coordinates to nodes, starting with node `0` at the origin
+    /// and its neighbors as unit vectors. Every other node gets the union of
+    /// coordinates of its neighbors closer to the origin.
+    /// The graph is a hypercube if this gives a bijection where
+    /// every edge connects nodes that differ in exactly one coordinate.
+    pub fn is_hypercube(&self) -> Option<usize> {
+        let count = self.nodes.len();
+        if !count.is_power_of_two() {return None};
+        let n = count.trailing_zeros() as usize;
+        if self.edges.len() != n * count / 2 {return None};
+        if self.is_regular() != Some(n) {return None};
+        let mut dist = self.distance(0).ok()?;
+        dist.sort_by_key(|n| n.1);
+        let mut coords: Vec<Option<usize>> = vec![None; count];
+        coords[0] = Some(0);
+        let mut unit = 0;
+        for &(i, d) in &dist[1..] {
+            let mut coord = 0;
+            let mut children = 0;
+            for j in self.edges_of(i) {
+                if let Some(c) = coords[j] {
+                    coord |= c;
+                    children += 1;
+                }
+            }
+            if d == 1 {
+                coord = 1 << unit;
+                unit += 1;
+            }
+            if children != d || coord.count_ones() as u64 != d {return None};
+            coords[i] = Some(coord);
+        }
+        let coords: Vec<usize> = coords.into_iter().map(|c| c.unwrap()).collect();
+        let mut sorted = coords.clone();
+        sorted.sort();
+        if sorted != (0..count).collect::<Vec<usize>>() {return None};
+        for &(a, b) in &self.edges {
+            if (coords[a] ^ coords[b]).count_ones() != 1 {return None};
+        }
+        Some(n)
+    }
+
+    /// Returns a uniformly random spanning tree using Wilson's algorithm.
+    ///
+    /// Uses loop-erased random walks, such that every spanning tree
+    /// is equally likely for random seeds.
+    ///
+    /// Returns `None` if the graph is disconnected.
+    pub fn random_spanning_tree(&self, seed: u64) -> Option<Graph<L>>
+        where L: Clone
+    {
+        let n = self.nodes.len();
+        let mut tree = Graph::default();
+        for node in &self.nodes {
+            tree.add_node(Node::with_label(node.core, node.label.clone()));
+        }
+        if n == 0 {return Some(tree)};
+        if !self.is_connected() {return None};
+
+        let edges: Vec<Vec<usize>> = (0..n)
+            .map(|i| self.edges_of(i).into_iter().filter(|&j| j != i).collect())
+            .collect();
+        let mut state = seed;
+        let mut in_tree = vec![false; n];
+        let mut next = vec![0; n];
+        in_tree[0] = true;
+        for i in 1..n {
+            // Random walk until hitting the tree,
+            // where revisiting a node overwrites its previous exit.
+            let mut u = i;
+            while !in_tree[u] {
+                let k = (splitmix64(&mut state) % edges[u].len() as u64) as usize;
+                next[u] = edges[u][k];
+                u = next[u];
+            }
+            // Add the loop-erased path to the tree.
+            let mut u = i;
+            while !in_tree[u] {
+                in_tree[u] = true;
+                tree.add_edge(u, next[u]);
+                u = next[u];
+            }
+        }
+        Some(tree)
+    }
+
+    /// Returns `true` if the graph is isomorphic to another graph.
+    ///
+    /// Cores and unique edges are ignored.
+    /// Uses backtracking, pruned by node degrees.
+    pub fn is_isomorphic(&self, other: &Graph<L>) -> bool {
+        let mut found = false;
+        self.isomorphisms(other, &mut |_| {
+            found = true;
+            false
+        });
+        found
+    }
+
+    /// Returns the graph with nodes in a canonical order.
+    ///
+    /// Two graphs are isomorphic, preserving cores and unique edges,
+    /// if and only if their normalized graphs are equal.
+    /// Edges are sorted.
+    ///
+    /// Uses color refinement and individualization, skipping nodes that are
+    /// equivalent by automorphisms found during the search.
+    /// This is exponential in the worst case.
+    pub fn normalize(&self) -> Graph<L>
+        where L: Clone + Ord
+    {
+        let n = self.nodes.len();
+        let edges: Vec<Vec<usize>> = (0..n).map(|i| self.edges_of(i)).collect();
+        let uniq: Vec<Option<usize>> = self.nodes.iter().map(|node| node.uniq).collect();
+        let mut data: Vec<(bool, &L)> = self.nodes.iter().map(|node| (node.core, &node.label)).collect();
+        data.sort();
+        data.dedup();
+        let colors = self.nodes.iter()
+            .map(|node| data.binary_search(&(node.core, &node.label)).unwrap())
+            .collect();
+        let colors = refine_colors(&edges, &uniq, colors);
+        let mut best = None;
+        canonical_search(self, &edges, &uniq, colors, &mut vec![], &mut best, &mut vec![]);
+        best.map(|(g, _)| g).unwrap_or_default()
+    }
+
+    /// Returns a hash that is equal for isomorphic graphs.
+    ///
+    /// Cores, unique edges and labels are taken into account.
+    /// The hash is the same across runs and platforms, if the hash of labels is.
+    pub fn canonical_hash(&self) -> u64
+        where L: Clone + Ord + Hash
+    {
+        let g = self.normalize();
+        let mut hasher = StableHasher::new();
+        for node in &g.nodes {
+            node.core.hash(&mut hasher);
+            node.uniq.hash(&mut hasher);
+            node.label.hash(&mut hasher);
+        }
+        g.edges.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns all automorphisms of the graph.
+    ///
+    /// Each automorphism is a permutation where entry `i` is the image of node `i`.
+    /// Cores and unique edges are ignored.
+    pub fn automorphisms(&self) -> Vec<Vec<usize>> {
+        let mut res = vec![];
+        self.isomorphisms(self, &mut |map| {
+            res.push(map.to_vec());
+            true
+        });
+        res.sort();
+        res
+    }
+
+    /// Calls `f` with every isomorphism from this graph to another graph.
+    ///
+    /// An isomorphism maps each node of this graph to a node of the other graph.
+    /// Stops when `f` returns `false`.
+    fn isomorphisms(&self, other: &Graph<L>, f: &mut dyn FnMut(&[usize]) -> bool) {
+        let n = self.nodes.len();
+        if n != other.nodes.len() {return};
+        let adjacency = |g: &Graph<L>| {
+            let mut adj = vec![vec![false; n]; n];
+            for &(a, b) in &g.edges {
+                adj[a][b] = true;
+                adj[b][a] = true;
+            }
+            adj
+        };
+        let a = adjacency(self);
+        let b = adjacency(other);
+        let degrees = |adj: &[Vec<bool>]| -> Vec<usize> {
+            adj.iter().map(|row| row.iter().filter(|&&x| x).count()).collect()
+        };
+        let da = degrees(&a);
+        let db = degrees(&b);
+        let mut sa = da.clone();
+        let mut sb = db.clone();
+        sa.sort();
+        sb.sort();
+        if sa != sb {return};
+
+        // Breadth first search from nodes of high degree,
+        // such that most nodes are constrained by earlier nodes.
+        let mut order = vec![];
+        let mut visited = vec![false; n];
+        let mut starts: Vec<usize> = (0..n).collect();
+        starts.sort_by_key(|&i| std::cmp::Reverse(da[i]));
+        for &start in &starts {
+            if visited[start] {continue};
+            visited[start] = true;
+            let mut i = order.len();
+            order.push(start);
+            while i < order.len() {
+                for j in 0..n {
+                    if a[order[i]][j] && !visited[j] {
+                        visited[j] = true;
+                        order.push(j);
+                    }
+                }
+                i += 1;
+            }
+        }
+        isomorphism_search(&a, &b, &da, &db, &order, &mut vec![None; n], &mut vec![false; n], f);
+    }
+
+    /// Returns the subgraph induced by a list of nodes.
+    ///
+    /// Nodes are re-indexed by their position in the list.
+    /// Unique edges to nodes outside the list are removed.
+    pub fn induced_subgraph(&self, nodes: &[usize]) -> Graph<L>
+        where L: Clone
+    {
+        let mut map: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        for (i, &j) in nodes.iter().enumerate() {
+            map[j] = Some(i);
+        }
+        let mut g = Graph::default();
+        for &j in nodes {
+            let mut node = self.nodes[j].clone();
+            node.uniq = node.uniq.and_then(|k| map[k]);
+            g.nodes.push(node);
+        }
+        for &(a, b) in &self.edges {
+            if let (Some(a), Some(b)) = (map[a], map[b]) {
+                g.edges.push((a.min(b), a.max(b)));
+            }
+        }
+        g
+    }
+}
+
+/// Searches collections of graphs by structural properties.
+///
+/// A graph matches when it satisfies all predicates.
+#[derive(Debug, Clone, Default)]
+pub struct GraphQuery {
+    core_count: Option<usize>,
+    diameter: Option<u64>,
+    fill_ratio_above: Option<f64>,
+    node_count: Option<usize>,
+    edge_count: Option<usize>,
+}
+
+impl GraphQuery {
+    /// Creates a new query that matches all graphs.
+    pub fn new() -> GraphQuery {
+        GraphQuery::default()
+    }
+
+    /// Requires an exact number of cores.
+    pub fn with_core_count(mut self, n: usize) -> GraphQuery {
+        self.core_count = Some(n);
+        self
+    }
+
+    /// Requires the graph to be connected with an exact diameter.
+    pub fn with_diameter(mut self, d: u64) -> GraphQuery {
+        self.diameter = Some(d);
+        self
+    }
+
+    /// Requires the fill ratio to be greater than `f`.
+    pub fn with_fill_ratio_above(mut self, f: f64) -> GraphQuery {
+        self.fill_ratio_above = Some(f);
+        self
+    }
+
+    /// Requires an exact number of nodes.
+    pub fn with_node_count(mut self, n: usize) -> GraphQuery {
+        self.node_count = Some(n);
+        self
+    }
+
+    /// Requires an exact number of edges.
+    pub fn with_edge_count(mut self, n: usize) -> GraphQuery {
+        self.edge_count = Some(n);
+        self
+    }
+
+    /// Returns `true` if a graph satisfies all predicates.
+    pub fn matches<L>(&self, g: &Graph<L>) -> bool {
+        if let Some(n) = self.node_count {
+            if g.nodes.len() != n {return false};
+        }
+        if let Some(n) = self.edge_count {
+            if g.edges.len() != n {return false};
+        }
+        if let Some(n) = self.core_count {
+            if g.cores() != n {return false};
+        }
+        if let Some(f) = self.fill_ratio_above {
+            if g.fill_ratio() <= f {return false};
+        }
+        if let Some(d) = self.diameter {
+            if g.diameter() != Some(d) {return false};
+        }
+        true
+    }
+
+    /// Returns indices of graphs that satisfy all predicates.
+    pub fn search<L>(&self, graphs: &[Graph<L>]) -> Vec<usize> {
+        (0..graphs.len()).filter(|&i| self.matches(&graphs[i])).collect()
+    }
+}
+
+/// Stores avatar distances from every node, for repeated queries.
+///
+/// The cache does not track changes to the graph.
+/// When the graph changes, every affected entry must be invalidated,
+/// or the cache rebuilt, otherwise cached queries may give wrong results or panic.
+#[derive(Debug, Clone)]
+pub struct AvatarCache {
+    dist: Vec<Option<Vec<(usize, u64)>>>,
+}
+
+impl AvatarCache {
+    /// Computes avatar distances from every node.
+    pub fn build<L>(graph: &Graph<L>) -> AvatarCache {
+        AvatarCache {
+            dist: (0..graph.nodes.len()).map(|i| Some(graph.avatar_distance(i))).collect(),
+        }
+    }
+
+    /// Returns avatar distances from a node, as computed by `Graph::avatar_distance`.
+    ///
+    /// Returns an empty slice if the entry is invalidated.
+    /// Panics if `ind` is out of range.
+    pub fn get(&self, ind: usize) -> &[(usize, u64)] {
+        self.dist[ind].as_deref().unwrap_or(&[])
+    }
+
+    /// Removes the avatar distances from a node.
+    pub fn invalidate(&mut self, ind: usize) {
+        if let Some(dist) = self.dist.get_mut(ind) {*dist = None}
+    }
+}
+
+/// Visits nodes in breadth first order, together with their depth.
+///
+/// Neighbors are visited in increasing order.
+pub struct BfsDepthIter {
+    adj: Vec<Vec<usize>>,
+    visited: Vec<bool>,
+    queue: std::collections::VecDeque<(usize, u64)>,
+}
+
+impl Iterator for BfsDepthIter {
+    type Item = (usize, u64);
+
+    fn next(&mut self) -> Option<(usize, u64)> {
+        let (i, d) = self.queue.pop_front()?;
+        for &j in &self.adj[i] {
+            if !self.visited[j] {
+                self.visited[j] = true;
+                self.queue.push_back((j, d + 1));
+            }
+        }
+        Some((i, d))
+    }
+}
+
+/// Visits nodes in breadth first order.
+///
+/// Neighbors are visited in increasing order.
+pub struct BfsIter {
+    inner: BfsDepthIter,
+}
+
+impl Iterator for BfsIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        self.inner.next().map(|n| n.0)
+    }
+}
+
+/// Visits nodes in depth first order.
+///
+/// Neighbors are visited in increasing order.
+pub struct DfsIter {
+    adj: Vec<Vec<usize>>,
+    visited: Vec<bool>,
+    stack: Vec<usize>,
+}
+
+impl Iterator for DfsIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            let i = self.stack.pop()?;
+            if self.visited[i] {continue};
+            self.visited[i] = true;
+            for &j in self.adj[i].iter().rev() {
+                if !self.visited[j] {self.stack.push(j)}
+            }
+            return Some(i);
+        }
+    }
+}
+
+/// Returns the max avatars and their avatar distance, from a list of avatar distances.
+fn max_avatars_of(dist: &[(usize, u64)]) -> (u64, Vec<usize>) {
+    let mut max = 0;
+    let mut avatars = vec![];
+    for &(a, n) in dist {
+        if n > max {
+            avatars.clear();
+            avatars.push(a);
+            max = n;
+        } else if n == max {
+            avatars.push(a);
+        }
+    }
+    (max, avatars)
+}
+
+/// Finds biconnected components by depth first search, as lists of edges.
+#[allow(clippy::too_many_arguments)]
+fn biconnected_visit(
+    u: usize,
+    parent: usize,
+    adj: &[Vec<usize>],
+    disc: &mut [usize],
+    low: &mut [usize],
+    time: &mut usize,
+    stack: &mut Vec<(usize, usize)>,
+    blocks: &mut Vec<Vec<(usize, usize)>>
+) {
+    disc[u] = *time;
+    low[u] = *time;
+    *time += 1;
+    for &v in &adj[u] {
+        if disc[v] == usize::MAX {
+            stack.push((u, v));
+            biconnected_visit(v, u, adj, disc, low, time, stack, blocks);
+            low[u] = low[u].min(low[v]);
+            if low[v] >= disc[u] {
+                // `u` separates the block containing `(u, v)`.
+                let mut block = vec![];
+                while let Some(e) = stack.pop() {
+                    block.push(e);
+                    if e == (u, v) {break};
+                }
+                blocks.push(block);
+            }
+        } else if v != parent && disc[v] < disc[u] {
+            stack.push((u, v));
+            low[u] = low[u].min(disc[v]);
+        }
+    }
+}
+
+/// Returns `true` if a biconnected component is planar.
+///
+/// Starts with a cycle and embeds one path of a fragment at a time,
+/// preferring fragments that fit in only one face.
+fn planar_block(block: &[(usize, usize)]) -> bool {
+    let mut vertices: Vec<usize> = block.iter().flat_map(|&(a, b)| vec![a, b]).collect();
+    vertices.sort();
+    vertices.dedup();
+    let n = vertices.len();
+    if n < 5 {return true};
+    if block.len() > 3 * n - 6 {return false};
+    let index = |v: usize| vertices.binary_search(&v).unwrap();
+    let mut adj: Vec<Vec<usize>> = vec![vec![]; n];
+    for &(a, b) in block {
+        adj[index(a)].push(index(b));
+        adj[index(b)].push(index(a));
+    }
+
+    // Find a cycle through the edge `(0, w)` by searching a path from `w` to `0`.
+    let w = adj[0][0];
+    let mut parent = vec![usize::MAX; n];
+    parent[w] = w;
+    let mut queue = std::collections::VecDeque::new();
+    queue.push_back(w);
+    while let Some(u) = queue.pop_front() {
+        for &v in &adj[u] {
+            if parent[v] != usize::MAX || (u == w && v == 0) {continue};
+            parent[v] = u;
+            queue.push_back(v);
+        }
+    }
+    let mut cycle = vec![0];
+    let mut u = parent[0];
+    while u != w {
+        cycle.push(u);
+        u = parent[u];
+    }
+    cycle.push(w);
+
+    let mut in_h = vec![false; n];
+    let mut h_edges = vec![vec![false; n]; n];
+    for i in 0..cycle.len() {
+        let (a, b) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+        in_h[a] = true;
+        h_edges[a][b] = true;
+        h_edges[b][a] = true;
+    }
+    let mut embedded = cycle.len();
+    let mut faces = vec![cycle.clone(), cycle];
+    while embedded < block.len() {
+        // Fragments are stored as attachments and a path between two attachments.
+        let mut fragments: Vec<(Vec<usize>, Vec<usize>)> = vec![];
+        for a in 0..n {
+            for &b in &adj[a] {
+                if a < b && in_h[a] && in_h[b] && !h_edges[a][b] {
+                    fragments.push((vec![a, b], vec![a, b]));
+                }
+            }
+        }
+        let mut comp = vec![usize::MAX; n];
+        for start in 0..n {
+            if in_h[start] || comp[start] != usize::MAX {continue};
+            comp[start] = start;
+            let mut members = vec![start];
+            let mut attachments = vec![];
+            let mut i = 0;
+            while i < members.len() {
+                for &v in &adj[members[i]] {
+                    if in_h[v] {
+                        attachments.push(v);
+                    } else if comp[v] == usize::MAX {
+                        comp[v] = start;
+                        members.push(v);
+                    }
+                }
+                i += 1;
+            }
+            attachments.sort();
+            attachments.dedup();
+            // Find a path through the fragment between two attachments.
+            let a = attachments[0];
+            let mut parent = vec![usize::MAX; n];
+            let mut queue = std::collections::VecDeque::new();
+            for &v in &adj[a] {
+                if comp[v] == start && parent[v] == usize::MAX {
+                    parent[v] = a;
+                    queue.push_back(v);
+                }
+            }
+            let mut path = vec![];
+            'search: while let Some(u) = queue.pop_front() {
+                for &v in &adj[u] {
+                    if in_h[v] && v != a {
+                        path.push(v);
+                        let mut x = u;
+                        while x != a {
+                            path.push(x);
+                            x = parent[x];
+                        }
+                        path.push(a);
+                        break 'search;
+                    } else if comp[v] == start && parent[v] == usize::MAX {
+                        parent[v] = u;
+                        queue.push_back(v);
+                    }
+                }
+            }
+            fragments.push((attachments, path));
+        }
+
+        let mut choice: Option<(usize, usize)> = None;
+        for (i, (attachments, _)) in fragments.iter().enumerate() {
+            let admissible: Vec<usize> = (0..faces.len())
+                .filter(|&f| attachments.iter().all(|v| faces[f].contains(v)))
+                .collect();
+            match admissible.len() {
+                0 => return false,
+                1 => {
+                    choice = Some((i, admissible[0]));
+                    break;
+                }
+                _ => if choice.is_none() {choice = Some((i, admissible[0]))},
+            }
+        }
+        let (i, f) = choice.unwrap();
+        let path = &fragments[i].1;
+        for k in 0..path.len() - 1 {
+            let (a, b) = (path[k], path[k + 1]);
+            in_h[a] = true;
+            h_edges[a][b] = true;
+            h_edges[b][a] = true;
+        }
+        in_h[path[path.len() - 1]] = true;
+        embedded += path.len() - 1;
+
+        // Split the face along the path.
+        let face = faces.swap_remove(f);
+        let len = face.len();
+        let start = face.iter().position(|&v| v == path[0]).unwrap();
+        let end = face.iter().position(|&v| v == path[path.len() - 1]).unwrap();
+        let interior = &path[1..path.len() - 1];
+        let mut face1 = vec![];
+        let mut k = start;
+        while k != end {
+            face1.push(face[k]);
+            k = (k + 1) % len;
+        }
+        face1.push(face[end]);
+        face1.extend(interior.iter().rev());
+        let mut face2 = vec![];
+        let mut k = end;
+        while k != start {
+            face2.push(face[k]);
+            k = (k + 1) % len;
+        }
+        face2.push(face[start]);
+        face2.extend(interior.iter());
+        faces.push(face1);
+        faces.push(face2);
+    }
+    true
+}
+
+/// Returns `true` if the remaining nodes can be placed with bandwidth at most `b`.
+fn bandwidth_search(
+    adj: &[Vec<bool>],
+    b: usize,
+    order: &mut Vec<usize>,
+    pos: &mut Vec<Option<usize>>
+) -> bool {
+    let n = adj.len();
+    let p = order.len();
+    if p == n {return true};
+    for v in 0..n {
+        if pos[v].is_some() {continue};
+        // Placed neighbors must be within `b` positions.
+        if (0..n).any(|u| adj[v][u] && pos[u].map(|q| p - q > b).unwrap_or(false)) {continue};
+        order.push(v);
+        pos[v] = Some(p);
+        // The node that falls out of reach must have all neighbors placed.
+        let mut ok = p < b || (0..n).all(|u| !adj[order[p - b]][u] || pos[u].is_some());
+        if ok {
+            // Unplaced nodes must fit before the deadlines set by their placed neighbors.
+            let mut deadlines: Vec<usize> = (0..n)
+                .filter(|&u| pos[u].is_none())
+                .filter_map(|u| (0..n).filter(|&w| adj[u][w]).filter_map(|w| pos[w]).min())
+                .map(|q| q + b)
+                .collect();
+            deadlines.sort();
+            ok = deadlines.iter().enumerate().all(|(k, &d)| d > p + k);
+        }
+        if ok && bandwidth_search(adj, b, order, pos) {return true};
+        order.pop();
+        pos[v] = None;
+    }
+    false
+}
+
+/// Extends a partial isomorphism by mapping nodes in order.
+///
+/// Returns `false` if the search was stopped by `f`.
+#[allow(clippy::too_many_arguments)]
+fn isomorphism_search(
+    a: &[Vec<bool>],
+    b: &[Vec<bool>],
+    da: &[usize],
+    db: &[usize],
+    order: &[usize],
+    map: &mut Vec<Option<usize>>,
+    used: &mut Vec<bool>,
+    f: &mut dyn FnMut(&[usize]) -> bool
+) -> bool {
+    let p = order.iter().position(|&u| map[u].is_none());
+    let u = match p {
+        None => {
+            let map: Vec<usize> = map.iter().map(|v| v.unwrap()).collect();
+            return f(&map);
+        }
+        Some(p) => order[p],
+    };
+    for v in 0..b.len() {
+        if used[v] || da[u] != db[v] || a[u][u] != b[v][v] {continue};
+        // Mapped nodes must keep their adjacency to `u`.
+        if (0..a.len()).any(|w| map[w].map(|x| a[u][w] != b[v][x]).unwrap_or(false)) {continue};
+        map[u] = Some(v);
+        used[v] = true;
+        let go_on = isomorphism_search(a, b, da, db, order, map, used, f);
+        map[u] = None;
+        used[v] = false;
+        if !go_on {return false};
+    }
+    true
+}
+
+/// Refines node colors until neighbors of equally colored nodes have equal colors.
+///
+/// Colors are re-numbered by rank, such that the result only depends on graph structure.
+fn refine_colors(edges: &[Vec<usize>], uniq: &[Option<usize>], mut colors: Vec<usize>) -> Vec<usize> {
+    let n = colors.len();
+    let mut count = usize::MAX;
+    loop {
+        let signatures: Vec<_> = (0..n).map(|i| {
+            let mut neighbors: Vec<usize> = edges[i].iter().map(|&j| colors[j]).collect();
+            neighbors.sort();
+            let mut avatars: Vec<usize> = (0..n).filter(|&j| uniq[j] == Some(i)).map(|j| colors[j]).collect();
+            avatars.sort();
+            (colors[i], neighbors, uniq[i].map(|j| colors[j]), avatars)
+        }).collect();
+        let mut sorted = signatures.clone();
+        sorted.sort();
+        sorted.dedup();
+        colors = signatures.iter().map(|sig| sorted.binary_search(sig).unwrap()).collect();
+        if sorted.len() == count {break};
+        count = sorted.len();
+    }
+    colors
+}
+
+/// Searches for the smallest relabeling of a graph among refined colorings.
+///
+/// Each node is individualized in turn, within the first color shared by several nodes.
+/// The best relabeling is stored with the node at each position.
+/// When another relabeling gives the same graph, the two orders give an automorphism.
+/// Nodes in the same orbit as an already individualized node are skipped,
+/// using the automorphisms found so far that fix the individualized `path`.
+fn canonical_search<L: Clone + Ord>(
+    g: &Graph<L>,
+    edges: &[Vec<usize>],
+    uniq: &[Option<usize>],
+    colors: Vec<usize>,
+    path: &mut Vec<usize>,
+    best: &mut Option<(Graph<L>, Vec<usize>)>,
+    automorphisms: &mut Vec<Vec<usize>>
+) {
+    let n = colors.len();
+    let mut sizes = vec![0; n];
+    for &c in &colors {sizes[c] += 1}
+    match (0..n).find(|&c| sizes[c] > 1) {
+        None => {
+            let mut order = vec![0; n];
+            for (i, &c) in colors.iter().enumerate() {
+                order[c] = i;
+            }
+            let mut h = Graph {
+                nodes: order.iter().map(|&i| Node {
+                    core: g.nodes[i].core,
+                    uniq: g.nodes[i].uniq.map(|j| colors[j]),
+                    label: g.nodes[i].label.clone(),
+                }).collect(),
+                edges: g.edges.iter().map(|&(a, b)| {
+                    let (a, b) = (colors[a], colors[b]);
+                    (a.min(b), a.max(b))
+                }).collect(),
+            };
+            h.edges.sort();
+            fn key<L>(n: &Node<L>) -> (bool, Option<usize>, &L) {(n.core, n.uniq, &n.label)}
+            use std::cmp::Ordering;
+            let cmp = best.as_ref().map(|(b, _)| {
+                h.nodes.iter().map(key).cmp(b.nodes.iter().map(key))
+                    .then_with(|| h.edges.cmp(&b.edges))
+            }).unwrap_or(Ordering::Less);
+            match (cmp, best.as_ref()) {
+                (Ordering::Less, _) => *best = Some((h, order)),
+                (Ordering::Equal, Some((_, best_order))) => {
+                    automorphisms.push(colors.iter().map(|&c| best_order[c]).collect());
+                }
+                _ => {}
+            }
+        }
+        Some(c) => {
+            let mut explored = vec![];
+            for v in 0..n {
+                if colors[v] != c {continue};
+                if !explored.is_empty() {
+                    let fixing = automorphisms.iter()
+                        .filter(|a| path.iter().all(|&p| a[p] == p));
+                    let orbit = orbits(n, fixing);
+                    if explored.iter().any(|&u| orbit[u] == orbit[v]) {continue};
+                }
+                explored.push(v);
+                let split = colors.iter().enumerate()
+                    .map(|(i, &k)| 2 * k + (k == c && i != v) as usize)
+                    .collect();
+                path.push(v);
+                canonical_search(g, edges, uniq, refine_colors(edges, uniq, split), path, best, automorphisms);
+                path.pop();
+            }
+        }
+    }
+}
+
+/// Returns a representative for each node of its orbit under a set of permutations.
+fn orbits<'a>(n: usize, permutations: impl Iterator<Item = &'a Vec<usize>>) -> Vec<usize> {
+    let mut parent: Vec<usize> = (0..n).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for p in permutations {
+        for (i, &j) in p.iter().enumerate() {
+            let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+            parent[a] = b;
+        }
+    }
+    (0..n).map(|i| root(&mut parent, i)).collect()
+}
+
+/// Computes the chromatic polynomial by deletion-contraction.
+///
+/// Requires edges to be sorted and without duplicates or self edges.
+fn chromatic_polynomial(n: usize, mut edges: Vec<(usize, usize)>) -> Vec<i64> {
+    let (a, b) = match edges.pop() {
+        None => {
+            let mut res = vec![0; n + 1];
+            res[n] = 1;
+            return res;
+        }
+        Some(e) => e,
+    };
+    // Contract by merging `b` into `a`.
+    let mut contracted: Vec<(usize, usize)> = edges.iter().map(|&(x, y)| {
+        let x = if x == b {a} else if x > b {x - 1} else {x};
+        let y = if y == b {a} else if y > b {y - 1} else {y};
+        (x.min(y), x.max(y))
+    }).collect();
+    contracted.sort();
+    contracted.dedup();
+    let mut res = chromatic_polynomial(n, edges);
+    for (i, c) in chromatic_polynomial(n - 1, contracted).into_iter().enumerate() {
+        res[i] -= c;
+    }
+    res
+}
+
+/// Branch-and-bound search for a maximum independent set among candidates.
+fn independent_set_search(
+    adj: &[Vec<bool>],
+    candidates: Vec<usize>,
+    current: &mut Vec<usize>,
+    best: &mut Vec<usize>
+) {
+    if current.len() + candidates.len() <= best.len() {return};
+    // Branch on the candidate with most neighbors among candidates.
+    let mut max: Option<(usize, usize)> = None;
+    for &i in &candidates {
+        let degree = candidates.iter().filter(|&&j| adj[i][j]).count();
+        if max.map(|m| m.1 < degree).unwrap_or(true) {max = Some((i, degree))}
+    }
+    match max {
+        Some((i, degree)) if degree > 0 => {
+            current.push(i);
+            let rest = candidates.iter().cloned().filter(|&j| j != i && !adj[i][j]).collect();
+            independent_set_search(adj, rest, current, best);
+            current.pop();
+            let rest = candidates.into_iter().filter(|&j| j != i).collect();
+            independent_set_search(adj, rest, current, best);
+        }
+        _ => {
+            // All candidates are mutually non-adjacent.
+            *best = current.clone();
+            best.extend(candidates);
+        }
+    }
+}
+
+/// Advances to the next subset in binary counting order, where `mask[0]` is the lowest bit.
+///
+/// Returns `false` when all subsets have been visited, starting from the empty set.
+fn next_subset(mask: &mut [bool]) -> bool {
+    match mask.iter().position(|&b| !b) {
+        None => false,
+        Some(i) => {
+            for b in &mut mask[..i] {*b = false}
+            mask[i] = true;
+            true
+        }
+    }
+}
+
+/// Generates a pseudo-random number and updates the state.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// A FNV-1a hasher.
+///
+/// Unlike `DefaultHasher`, the algorithm is fixed and integers are hashed as little endian,
+/// such that hashes can be stored and compared between runs.
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn new() -> StableHasher {
+        StableHasher(0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {self.0}
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn write_u8(&mut self, x: u8) {self.write(&[x])}
+    fn write_u16(&mut self, x: u16) {self.write(&x.to_le_bytes())}
+    fn write_u32(&mut self, x: u32) {self.write(&x.to_le_bytes())}
+    fn write_u64(&mut self, x: u64) {self.write(&x.to_le_bytes())}
+    fn write_u128(&mut self, x: u128) {self.write(&x.to_le_bytes())}
+    fn write_usize(&mut self, x: usize) {self.write_u64(x as u64)}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_graph() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        let b = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        assert_eq!(g.nodes.len(), 2);
+        assert_eq!(g.edges.len(), 1);
+        assert_eq!(g.node_count(), 2);
+        assert_eq!(g.edge_count(), 1);
+        assert_eq!(g.cores(), 1);
+        assert_eq!(g.non_cores(), 1);
+        assert_eq!(g.edges_of(a), vec![b]);
+        assert_eq!(g.edges_of(b), vec![a]);
+        assert_eq!(g.self_edges(), 0);
+        assert_eq!(g.matrix(), vec![
+            vec![0, 1],
+            vec![0, 0]
+        ]);
+        assert_eq!(g.unique_edges(), 0);
+    }
+
+    #[test]
+    fn remove_self_edges() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        g.add_edge(a, a);
+        assert_eq!(g.self_edges(), 1);
+        g.remove_self_edges();
+        assert_eq!(g.self_edges(), 0);
+        assert_eq!(g.matrix(), vec![
+            vec![0]
+        ]);
+        assert_eq!(g.unique_edges(), 0);
+    }
+
+    #[test]
+    fn unique_edge() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        let b = g.add_node(Node::new(false));
+        assert_eq!(g.matrix(), vec![
+            vec![0, 0],
+            vec![0, 0]
+        ]);
+        assert_eq!(g.unique_edges(), 0);
+        g.nodes[a].uniq = Some(b);
+        assert_eq!(g.unique_edges(), 1);
+        assert_eq!(g.matrix(), vec![
+            vec![0, 2],
+            vec![0, 0]
+        ]);
+        g.add_edge(a, b);
+        assert_eq!(g.matrix(), vec![
+            vec![0, 3],
+            vec![0, 0]
+        ]);
+        assert_eq!(g.unique_edges(), 1);
+    }
+
+    #[test]
+    fn self_unique_edge() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        assert_eq!(g.self_unique_edges(), 0);
+        g.nodes[a].uniq = Some(a);
+        assert_eq!(g.self_unique_edges(), 1);
+        g.remove_self_unique_edges();
+        assert_eq!(g.self_unique_edges(), 0);
+    }
+
+    #[test]
+    fn order() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        let b = g.add_node(Node::new(false));
+        assert_eq!(g.distance(a), Err(vec![(a, 0)]));
+        assert_eq!(g.distance(b), Err(vec![(b, 0)]));
+        g.add_edge(a, b);
+        assert_eq!(g.distance(a), Ok(vec![(a, 0), (b, 1)]));
+        assert_eq!(g.distance(b), Ok(vec![(a, 1), (b, 0)]));
+    }
+
+    #[test]
+    fn max_avatars() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(a, c);
+        g.add_edge(b, d);
+        g.add_edge(c, d);
+        assert_eq!(g.max_avatars(a), (2, vec![d]));
+    }
+
+    #[test]
+    fn avatar3() {
+        //      a ----- b
+        //      |       |  \
+        //      |       |    e
+        //      |       |  /
+        //      c ----- d
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        let e = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(a, c);
+        g.add_edge(b, d);
+        g.add_edge(c, d);
+        g.add_edge(b, e);
+        g.add_edge(d, e);
+        assert_eq!(g.avatar_distance(a), vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 3)]);
+    }
+
+    #[test]
+    fn contractible() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(b, c);
+        assert_eq!(g.contractible(a), 1);
+    }
+
+    #[test]
+    fn swap() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(a, c);
+        assert_eq!(g.edges, vec![(0, 1), (0, 2)]);
+        g.swap(a, b);
+        assert_eq!(g.edges, vec![(0, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn avatar_graph() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        let b = g.add_node(Node::new(false));
+        assert_eq!(g.is_avatar_graph(a), false);
+        g.add_edge(a, b);
+        assert_eq!(g.is_avatar_graph(a), true);
+        assert_eq!(g.is_avatar_graph(b), true);
+        let c = g.add_node(Node::new(false));
+        assert_eq!(g.is_avatar_graph(a), false);
+        g.add_edge(a, c);
+        assert_eq!(g.is_avatar_graph(a), false);
+        let d = g.add_node(Node::new(false));
+        assert_eq!(g.is_avatar_graph(a), false);
+        g.add_edge(c, d);
+        assert_eq!(g.is_avatar_graph(a), false);
+        g.add_edge(b, d);
+        assert_eq!(g.is_avatar_graph(a), true);
+    }
+
+    #[test]
+    fn corify() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(a, c);
+        g.add_edge(b, d);
+        g.add_edge(c, d);
+        g.corify();
+        assert_eq!(g.nodes[a].core, true);
+        assert_eq!(g.nodes[b].core, true);
+        assert_eq!(g.nodes[c].core, true);
+        assert_eq!(g.nodes[d].core, true);
+        assert_eq!(g.nodes[a].uniq, Some(d));
+        assert_eq!(g.nodes[b].uniq, Some(c));
+        assert_eq!(g.nodes[c].uniq, Some(b));
+        assert_eq!(g.nodes[d].uniq, Some(a));
+
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(b, c);
+        g.add_edge(c, a);
+        g.corify();
+        assert_eq!(g.cores(), 0);
+    }
+
+    #[test]
+    fn corify_cube() {
+        let mut g = Graph::new();
+        let a000 = g.add_node(Node::new(false));
+        let a100 = g.add_node(Node::new(false));
+        let a010 = g.add_node(Node::new(false));
+        let a001 = g.add_node(Node::new(false));
+        let a011 = g.add_node(Node::new(false));
+        let a101 = g.add_node(Node::new(false));
+        let a110 = g.add_node(Node::new(false));
+        let a111 = g.add_node(Node::new(false));
+        g.add_edge(a000, a100);
+        g.add_edge(a000, a010);
+        g.add_edge(a000, a001);
+        g.add_edge(a100, a110);
+        g.add_edge(a100, a101);
+        g.add_edge(a010, a110);
+        g.add_edge(a010, a011);
+        g.add_edge(a001, a101);
+        g.add_edge(a001, a011);
+        g.add_edge(a011, a111);
+        g.add_edge(a101, a111);
+        g.add_edge(a110, a111);
+        g.corify();
+        assert_eq!(g.cores(), 8);
+
+
+        let mut g = Graph::new();
+        let a000 = g.add_node(Node::new(false));
+        let a110 = g.add_node(Node::new(false));
+        let a101 = g.add_node(Node::new(false));
+        let a100 = g.add_node(Node::new(false));
+        let a111 = g.add_node(Node::new(false));
+        let a010 = g.add_node(Node::new(false));
+        let a001 = g.add_node(Node::new(false));
+        let a011 = g.add_node(Node::new(false));
+        g.add_edge(a010, a011);
+        g.add_edge(a001, a011);
+        g.add_edge(a000, a010);
+        g.add_edge(a010, a110);
+        g.add_edge(a101, a111);
+        g.add_edge(a000, a001);
+        g.add_edge(a011, a111);
+        g.add_edge(a100, a110);
+        g.add_edge(a100, a101);
+        g.add_edge(a000, a100);
+        g.add_edge(a001, a101);
+        g.add_edge(a110, a111);
+        g.corify();
+        assert_eq!(g.cores(), 8);
+    }
+
+    #[test]
+    fn corify_cube4() {
+        let mut g = Graph {
+            nodes: vec![Node::new(false); 16],
+            edges: vec![
+                (0, 3), (2, 3), (1, 2), (0, 1),
+                (0, 4), (4, 7), (3, 7), (6, 7),
+                (2, 6), (5, 6), (1, 5), (4, 5),
+                (8, 15), (12, 15), (9, 12), (8, 9),
+                (9, 11), (10, 11), (8, 10), (10, 14),
+                (13, 14), (11, 13), (12, 13), (14, 15),
+                (4, 15), (5, 12), (1, 9), (0, 8),
+                (6, 13), (7, 14), (3, 10), (2, 11)
+            ]
+        };
+        g.corify();
+        assert_eq!(g.cores(), 16);
+    }
+
+    #[test]
+    fn corify_5() {
+        let mut g = Graph {
+            nodes: vec![Node::new(false); 5],
+            edges: vec![
+                (0, 1), (1, 2),
+                (2, 4), (3, 4),
+                (0, 3), (2, 3)
+            ]
+        };
+        g.corify();
+        assert_eq!(g.cores(), 2);
+    }
+
+    #[test]
+    fn corify_7() {
+        let mut g = Graph {
+            //     __ 6 __
+            //   4 __   __  5
+            //   | __ 2 __  |
+            //   0 __   __  1
+            //        3
+            nodes: vec![Node::new(false); 7],
+            edges: vec![
+                (0, 3), (1, 3), (1, 2),
+                (0, 2), (0, 4), (2, 4),
+                (2, 5), (1, 5), (5, 6),
+                (4, 6)
+            ]
+        };
+        g.corify();
+        assert_eq!(g.cores(), 2);
+    }
+
+    #[test]
+    fn wagner() {
+        //              1
+        //         6    |    7
+        //    2 ------- | ------- 3
+        //         5    |    4
+        //              0
+        let mut g = Graph {
+            nodes: vec![Node::new(false); 8],
+            edges: vec![
+                (0, 1), (2, 3), (5, 7), (4, 6),
+                (0, 4), (0, 5), (2, 5), (2, 6),
+                (1, 6), (1, 7), (3, 7), (3, 4)
+            ]
+        };
+        g.corify();
+        assert_eq!(g.cores(), 8);
+    }
+
+    #[test]
+    fn corify_8() {
+        //        0
+        //     4 _  _ 6
+        //  2   _ X _     3
+        //     7      5
+        //        1
+        let mut g = Graph {
+            nodes: vec![Node::new(false); 8],
+            edges: vec![
+                (0, 6), (3, 6), (3, 5),
+                (1, 5), (1, 7), (2, 7),
+                (2, 4), (0, 4), (4, 5),
+                (6, 7)
+            ]
+        };
+        g.corify();
+        assert_eq!(g.cores(), 8);
+    }
+
+    #[test]
+    fn corify_9() {
+        //                   8
+        //              /          \
+        //          /                  \
+        //        0------1-------2-------3
+        //        |        \   /         |
+        //        |         \/           |
+        //        |         /\           |
+        //        |       /    \         |
+        //        4------5-------6-------7
+        //          \                  /
+        //              \         /
+        //                   9
+        let mut g = Graph {
+            nodes: vec![Node { core: false, uniq: None, label: () }; 10],
+            edges: vec![
+                (0, 8), (3, 8), (0, 1), (1, 2),
+                (2, 3), (0, 4), (1, 6), (2, 5),
+                (3, 7), (4, 5), (5, 6), (6, 7),
+                (4, 9), (7, 9)
+            ]
+        };
+        g.corify();
+        // assert_eq!(g.cores(), 4);
+    }
+
+    #[test]
+    fn corify_10() {
+        //  0 ------- 1
+        //  |         |
+        //  2         |
+        // 4 3 ------ 5
+        let mut g = Graph {
+            nodes: vec![Node { core: false, uniq: None, label: () }; 6],
+            edges: vec![
+                (0, 1), (0, 2), (2, 4), (3, 4),
+                (2, 3), (3, 5), (1, 5)
+            ]
+        };
+        g.corify();
+        // assert_eq!(g.cores(), 3);
+    }
+
+    #[test]
+    fn avatar_graph_entropy() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(a, c);
+        g.add_edge(b, d);
+        g.add_edge(c, d);
+        // Distances `0, 1, 1, 2` from every core.
+        assert_eq!(g.avatar_graph_entropy(), 1.5);
+
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(b, c);
+        g.add_edge(c, a);
+        assert_eq!(g.avatar_graph_entropy(), 0.0);
+    }
+
+    #[test]
+    fn avatar_balance() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        assert_eq!(g.avatar_balance(a), 1.0);
+
+        // Star with center `a` has all avatars at level 1.
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        let e = g.add_node(Node::new(false));
+        g.add_edge(a, c);
+        g.add_edge(a, d);
+        g.add_edge(a, e);
+        assert!((g.avatar_balance(a) - 0.4).abs() < 1e-10);
+    }
+
+    #[test]
+    fn wl_hash() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(b, c);
+        g.add_edge(c, d);
+        let mut h = g.clone();
+        h.swap(a, c);
+        assert_eq!(g.wl_hash(3), h.wl_hash(3));
+        g.add_edge(d, a);
+        assert_ne!(g.wl_hash(3), h.wl_hash(3));
+    }
+
+    #[test]
+    fn node_importance() {
+        let g = named_graphs::wagner();
+        let importance = g.node_importance();
+        assert_eq!(importance.len(), 8);
+        assert!(importance.iter().all(|n| n.1 == importance[0].1));
+
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(b, c);
+        g.corify();
+        assert_eq!(g.fill_ratio(), 0.0);
+        // Removing an end node leaves a filled graph.
+        assert_eq!(g.node_importance(), vec![(b, 0.0), (a, -1.0), (c, -1.0)]);
+    }
+
+    #[test]
+    fn connected_induced_subgraphs() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(a, c);
+        g.add_edge(b, d);
+        g.add_edge(c, d);
+        let subgraphs: Vec<Graph> = g.connected_induced_subgraphs().collect();
+        assert_eq!(subgraphs.len(), 13);
+        assert_eq!(subgraphs.iter().filter(|h| h.nodes.len() == 1).count(), 4);
+        assert_eq!(subgraphs.iter().filter(|h| h.nodes.len() == 2).count(), 4);
+        assert_eq!(subgraphs.iter().filter(|h| h.nodes.len() == 3).count(), 4);
+        assert_eq!(subgraphs.iter().filter(|h| h.edges.len() == 4).count(), 1);
+        assert!(subgraphs.iter().all(|h| *h == h.normalize()));
+        // The four paths of three nodes have the same normal form.
+        let paths: Vec<&Graph> = subgraphs.iter().filter(|h| h.nodes.len() == 3).collect();
+        assert!(paths.iter().all(|h| h == &paths[0]));
+
+        // Large graphs are enumerated lazily.
+        let first: Vec<Graph> = Graph::path(70).connected_induced_subgraphs().take(3).collect();
+        assert_eq!(first.iter().map(|h| h.nodes.len()).collect::<Vec<_>>(), vec![1, 1, 2]);
+    }
+
+    #[test]
+    fn random_spanning_tree() {
+        let g = Graph::hypercube(3);
+        let mut trees = vec![];
+        for seed in 0..1000 {
+            let mut tree = g.random_spanning_tree(seed).unwrap();
+            assert_eq!(tree.edges.len(), 7);
+            assert!(tree.distance(0).is_ok());
+            assert!(tree.edges.iter().all(|e| g.edges.contains(e)));
+            tree.edges.sort();
+            if !trees.contains(&tree.edges) {trees.push(tree.edges)}
+        }
+        assert!(trees.len() > 300 && trees.len() <= 384);
+
+        let mut g = Graph::new();
+        g.add_node(Node::new(false));
+        g.add_node(Node::new(false));
+        assert!(g.random_spanning_tree(0).is_none());
+    }
+
+    #[test]
+    fn matrix_power() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(a, c);
+        g.add_edge(b, d);
+        g.add_edge(c, d);
+        assert_eq!(g.matrix_power(0), vec![
+            vec![1, 0, 0, 0],
+            vec![0, 1, 0, 0],
+            vec![0, 0, 1, 0],
+            vec![0, 0, 0, 1],
+        ]);
+        assert_eq!(g.matrix_power(1), vec![
+            vec![0, 1, 1, 0],
+            vec![1, 0, 0, 1],
+            vec![1, 0, 0, 1],
+            vec![0, 1, 1, 0],
+        ]);
+        assert_eq!(g.matrix_power(2), vec![
+            vec![2, 0, 0, 2],
+            vec![0, 2, 2, 0],
+            vec![0, 2, 2, 0],
+            vec![2, 0, 0, 2],
+        ]);
+
+        let g = Graph::complete(10);
+        assert_eq!(g.matrix_power(2)[0][1], 8);
+        assert_eq!(g.matrix_power(40)[0][1], u64::MAX);
+    }
+
+    #[test]
+    fn degree_stats() {
+        let g = named_graphs::wagner();
+        assert_eq!(g.max_degree(), 3);
+        assert_eq!(g.min_degree(), 3);
+        assert_eq!(g.avg_degree(), 3.0);
+
+        let g = Graph {
+            nodes: vec![Node::new(false); 5],
+            edges: vec![(0, 1), (0, 2), (0, 3), (0, 4)]
+        };
+        assert_eq!(g.max_degree(), 4);
+        assert_eq!(g.min_degree(), 1);
+        assert_eq!(g.avg_degree(), 8.0 / 5.0);
+
+        let g = Graph::new();
+        assert_eq!(g.max_degree(), 0);
+        assert_eq!(g.min_degree(), 0);
+        assert_eq!(g.avg_degree(), 0.0);
+    }
+
+    #[test]
+    fn isolated_and_leaf_nodes() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(b, c);
+        assert_eq!(g.isolated_nodes(), vec![d]);
+        assert_eq!(g.leaf_nodes(), vec![a, c]);
+    }
+
+    #[test]
+    fn avatar_distance_histogram() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        let e = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(a, c);
+        g.add_edge(b, d);
+        g.add_edge(c, d);
+        g.add_edge(b, e);
+        g.add_edge(d, e);
+        assert_eq!(g.avatar_distance_histogram(a), vec![(0, 1), (1, 2), (2, 1), (3, 1)]);
+
+        let g = Graph::hypercube(3);
+        assert_eq!(g.avatar_distance_histogram(0), vec![(0, 1), (1, 3), (2, 3), (3, 1)]);
+    }
+
+    #[test]
+    fn reserve() {
+        let mut g = Graph::new();
+        g.reserve_nodes(10);
+        g.reserve_edges(20);
+        assert!(g.nodes.capacity() >= 10);
+        assert!(g.edges.capacity() >= 20);
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.shrink_to_fit();
+        assert!(g.nodes.capacity() < 10);
+        assert!(g.edges.capacity() < 20);
+    }
+
+    #[test]
+    fn independence_number() {
+        let g = Graph::hypercube(3);
+        assert_eq!(g.independence_number(), Some(4));
+
+        let g = named_graphs::wagner();
+        assert_eq!(g.independence_number(), Some(3));
+
+        let g = named_graphs::petersen();
+        assert_eq!(g.independence_number(), Some(4));
+
+        let g = Graph::complete(5);
+        assert_eq!(g.independence_number(), Some(1));
+
+        let g = Graph {
+            nodes: vec![Node::new(false); 2],
+            edges: vec![(0, 0)]
+        };
+        assert_eq!(g.independence_number(), Some(1));
+
+        let g = Graph {
+            nodes: vec![Node::new(false); 21],
+            edges: vec![]
+        };
+        assert_eq!(g.independence_number(), None);
+    }
+
+    #[test]
+    fn wl2_hash() {
+        let cube = Graph::hypercube(3);
+        let wagner = named_graphs::wagner();
+        // Both graphs are 3-regular, which 1-dimensional WL can not distinguish.
+        assert_eq!(cube.wl_hash(8), wagner.wl_hash(8));
+        assert_ne!(cube.wl2_hash(), wagner.wl2_hash());
+        let mut cube2 = cube.clone();
+        cube2.swap(0, 7);
+        cube2.swap(2, 5);
+        assert_eq!(cube.wl2_hash(), cube2.wl2_hash());
+    }
+
+    #[test]
+    fn planar() {
+        assert!(Graph::complete(4).is_planar());
+        assert!(!Graph::complete(4).is_outerplanar());
+        assert!(!Graph::complete(5).is_planar());
+
+        let mut k33 = named_graphs::complete_bipartite(3, 3);
+        assert!(!k33.is_planar());
+        k33.edges.pop();
+        assert!(k33.is_planar());
+        assert!(!k33.is_outerplanar());
+
+        let cube = Graph::hypercube(3);
+        assert!(cube.is_planar());
+        assert!(!cube.is_outerplanar());
+
+        let petersen = named_graphs::petersen();
+        assert!(!petersen.is_planar());
+
+        let mut cycle = Graph::cycle(6);
+        assert!(cycle.is_outerplanar());
+        cycle.add_edge(0, 3);
+        assert!(cycle.is_outerplanar());
+        cycle.add_edge(1, 4);
+        assert!(!cycle.is_outerplanar());
+        assert!(Graph::new().is_outerplanar());
+    }
+
+    #[test]
+    fn bandwidth() {
+        let mut path = Graph::path(6);
+        path.swap(0, 3);
+        assert_eq!(path.bandwidth(), 1);
+
+        assert_eq!(Graph::complete(5).bandwidth(), 4);
+        assert_eq!(Graph::complete(12).bandwidth(), 11);
+        assert_eq!(named_graphs::complete_bipartite(6, 6).bandwidth(), 8);
+
+        let cube = Graph::hypercube(3);
+        assert_eq!(cube.bandwidth(), 4);
+        assert_eq!(Graph::new().bandwidth(), 0);
+    }
+
+    #[test]
+    fn chromatic_polynomial() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        assert_eq!(g.chromatic_polynomial(), vec![0, -1, 1]);
+        let c = g.add_node(Node::new(false));
+        g.add_edge(b, c);
+        g.add_edge(c, a);
+        assert_eq!(g.chromatic_polynomial(), vec![0, 2, -3, 1]);
+
+        // `(k - 1)^4 + (k - 1)` for the 4-cycle.
+        let g = Graph {
+            nodes: vec![Node::new(false); 4],
+            edges: vec![(0, 1), (0, 2), (1, 3), (2, 3)]
+        };
+        assert_eq!(g.chromatic_polynomial(), vec![0, -3, 6, -4, 1]);
+        assert_eq!(Graph::new().chromatic_polynomial(), vec![1]);
+    }
+
+    #[test]
+    fn graph_query() {
+        let square = Graph::hypercube(2);
+        let triangle = Graph::complete(3);
+        let cube = Graph::hypercube(3);
+        let wagner = named_graphs::wagner();
+        let mut graphs = vec![square, triangle, cube, wagner];
+        for g in &mut graphs {g.corify()}
+
+        assert_eq!(GraphQuery::new().search(&graphs), vec![0, 1, 2, 3]);
+        assert_eq!(GraphQuery::new().with_fill_ratio_above(0.99).search(&graphs), vec![0, 2, 3]);
+        assert_eq!(GraphQuery::new().with_core_count(0).search(&graphs), vec![1]);
+        let query = GraphQuery::new()
+            .with_node_count(8)
+            .with_edge_count(12)
+            .with_fill_ratio_above(0.99);
+        assert_eq!(query.search(&graphs), vec![2, 3]);
+        assert_eq!(query.clone().with_diameter(3).search(&graphs), vec![2]);
+        assert_eq!(query.with_diameter(2).search(&graphs), vec![3]);
+    }
+
+    #[test]
+    fn minimum_vertex_cover() {
+        let cube = Graph::hypercube(3);
+        let cover = cube.minimum_vertex_cover();
+        assert_eq!(cover.len(), 4);
+        assert!(cube.edges.iter().all(|&(a, b)| cover.contains(&a) || cover.contains(&b)));
+
+        assert_eq!(Graph::complete(3).minimum_vertex_cover().len(), 2);
+
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        g.add_node(Node::new(false));
+        g.add_edge(a, a);
+        assert_eq!(g.minimum_vertex_cover(), vec![a]);
+
+        // Large graphs get a cover from a maximal matching.
+        let g = Graph::path(21);
+        let cover = g.minimum_vertex_cover();
+        assert!(g.edges.iter().all(|&(a, b)| cover.contains(&a) || cover.contains(&b)));
+        assert!(cover.len() <= 20);
+    }
+
+    #[test]
+    fn face_count() {
+        let cube = Graph::hypercube(3);
+        assert_eq!(cube.face_count(), Some(6));
+
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(b, c);
+        assert_eq!(g.face_count(), Some(1));
+        g.add_edge(c, a);
+        assert_eq!(g.face_count(), Some(2));
+        g.add_node(Node::new(false));
+        assert_eq!(g.face_count(), None);
+
+        assert_eq!(Graph::complete(5).face_count(), None);
+    }
+
+    #[test]
+    fn fingerprint() {
+        let cube = Graph::hypercube(3);
+        let wagner = named_graphs::wagner();
+        let mut cube2 = cube.clone();
+        cube2.swap(1, 7);
+        cube2.swap(0, 4);
+        assert_eq!(cube.fingerprint(), cube2.fingerprint());
+        assert_ne!(cube.fingerprint(), wagner.fingerprint());
+
+        let g = Graph::from_edges(5, vec![(0, 1), (0, 2), (0, 3), (0, 4), (1, 4), (3, 4)], vec![]);
+        for graph in [g, wagner, named_graphs::petersen(), Graph::path(6)] {
+            let n = graph.nodes.len();
+            let mut relabeled = graph.clone();
+            let mut state = 7;
+            for _ in 0..20 {
+                let r = splitmix64(&mut state);
+                relabeled.swap(r as usize % n, (r >> 32) as usize % n);
+                assert_eq!(relabeled.fingerprint(), graph.fingerprint());
+            }
+        }
+    }
+
+    #[test]
+    fn remove_node() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let x = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        let y = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(a, c);
+        g.add_edge(b, d);
+        g.add_edge(c, d);
+        g.add_edge(x, y);
+        g.nodes[a].uniq = Some(d);
+        g.nodes[y].uniq = Some(x);
+        assert!(g.remove_node(10).is_none());
+        assert!(g.remove_node(x).is_some());
+        assert_eq!(g.nodes.len(), 5);
+        assert_eq!(g.edges, vec![(0, 1), (0, 2), (1, 3), (2, 3)]);
+        assert_eq!(g.nodes[a].uniq, Some(d - 1));
+        // `y` lost its only edge and its unique edge.
+        assert_eq!(g.edges_of(y - 1), vec![]);
+        assert_eq!(g.nodes[y - 1].uniq, None);
+        g.remove_node(y - 1);
+        g.corify();
+        assert_eq!(g.cores(), 4);
+        assert_eq!(g.nodes[a].uniq, Some(d - 1));
+    }
+
+    #[test]
+    fn remove_edge() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(a, c);
+        g.add_edge(b, d);
+        g.add_edge(c, d);
+        g.add_edge(a, d);
+        g.corify();
+        assert_eq!(g.cores(), 0);
+        g.nodes[a].uniq = Some(d);
+        assert!(g.remove_edge(d, a));
+        assert!(!g.remove_edge(a, d));
+        assert_eq!(g.edges, vec![(0, 1), (0, 2), (1, 3), (2, 3)]);
+        assert_eq!(g.nodes[a].uniq, Some(d));
+        g.corify();
+        assert_eq!(g.cores(), 4);
+    }
+
+    #[test]
+    fn semi_contractible() {
+        // Same graph as `corify_8`, see `avatar8-04.png`.
+        let g = Graph {
+            nodes: vec![Node::new(false); 8],
+            edges: vec![
+                (0, 6), (3, 6), (3, 5),
+                (1, 5), (1, 7), (2, 7),
+                (2, 4), (0, 4), (4, 5),
+                (6, 7)
+            ]
+        };
+        assert_eq!(g.semi_contractibles_of(0), vec![5, 7]);
+        assert_eq!(g.semi_contractible(0), 2);
+        assert_eq!(g.contractible(0), 0);
+        assert!(g.is_avatar_graph(0));
+
+        let g = Graph {
+            nodes: vec![Node::new(false); 4],
+            edges: vec![(0, 1), (0, 2), (1, 3), (2, 3)]
+        };
+        assert_eq!(g.semi_contractible(0), 0);
+    }
+
+    #[test]
+    fn avatar_level() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(true));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        let e = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.add_edge(a, c);
+        g.add_edge(b, d);
+        g.add_edge(c, d);
+        g.add_edge(b, e);
+        g.add_edge(d, e);
+        assert_eq!(g.avatar_level(a), vec![(a, 0), (b, 1), (c, 1), (d, 2), (e, 3)]);
+        assert_eq!(g.k_avatars(a, 1), vec![b, c]);
+        assert_eq!(g.k_avatars(a, 3), vec![e]);
+        assert_eq!(g.k_avatars(a, 4), vec![]);
+
+        // Avatar distances of the cube are `0, 1, 2, 6`.
+        let g = Graph::hypercube(3);
+        assert_eq!(g.avatar_distance(0)[7], (7, 6));
+        assert_eq!(g.avatar_level(0)[7], (7, 3));
+        assert_eq!(g.k_avatars(0, 2), vec![3, 5, 6]);
+    }
+
+    #[test]
+    fn hypercube() {
+        let mut g = Graph::hypercube(2);
+        assert_eq!(g.edges, vec![(0, 1), (0, 2), (1, 3), (2, 3)]);
+        g.corify();
+        assert_eq!(g.cores(), 4);
+
+        let mut g = Graph::hypercube(4);
+        let h = Graph {
+            nodes: vec![Node::new(false); 16],
+            edges: vec![
+                (0, 3), (2, 3), (1, 2), (0, 1),
+                (0, 4), (4, 7), (3, 7), (6, 7),
+                (2, 6), (5, 6), (1, 5), (4, 5),
+                (8, 15), (12, 15), (9, 12), (8, 9),
+                (9, 11), (10, 11), (8, 10), (10, 14),
+                (13, 14), (11, 13), (12, 13), (14, 15),
+                (4, 15), (5, 12), (1, 9), (0, 8),
+                (6, 13), (7, 14), (3, 10), (2, 11)
+            ]
+        };
+        assert_eq!(g.nodes.len(), h.nodes.len());
+        assert_eq!(g.edges.len(), h.edges.len());
+        assert_eq!(g.wl2_hash(), h.wl2_hash());
+        g.corify();
+        assert_eq!(g.cores(), 16);
+        assert_eq!(Graph::hypercube(0).nodes.len(), 1);
+    }
+
+    #[test]
+    fn is_hypercube() {
+        for n in 0..6 {
+            assert_eq!(Graph::hypercube(n).is_hypercube(), Some(n));
+        }
+        let mut g = Graph::hypercube(3);
+        g.swap(0, 6);
+        g.swap(1, 4);
+        assert_eq!(g.is_hypercube(), Some(3));
+
+        let wagner = named_graphs::wagner();
+        assert_eq!(wagner.is_hypercube(), None);
+
+        let g = Graph {
+            nodes: vec![Node::new(false); 6],
+            edges: vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (0, 5)]
+        };
+        assert_eq!(g.is_hypercube(), None);
+        assert_eq!(Graph::new().is_hypercube(), None);
+    }
+
+    #[test]
+    fn connected_components() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        let c = g.add_node(Node::new(false));
+        let d = g.add_node(Node::new(false));
+        let e = g.add_node(Node::new(false));
+        g.add_edge(a, d);
+        g.add_edge(b, c);
+        g.add_edge(c, e);
+        let components = g.connected_components();
+        assert_eq!(components, vec![vec![a, d], vec![b, c, e]]);
+        let mut all: Vec<usize> = components.into_iter().flatten().collect();
+        all.sort();
+        assert_eq!(all, vec![a, b, c, d, e]);
+        assert!(!g.is_connected());
+        g.add_edge(d, e);
+        assert!(g.is_connected());
+        assert!(Graph::new().is_connected());
+    }
+
+    #[test]
+    fn cartesian_product() {
+        let mut edge_graph = Graph::new();
+        let a = edge_graph.add_node(Node::new(true));
+        let b = edge_graph.add_node(Node::new(true));
+        edge_graph.add_edge(a, b);
+        let mut square = Graph::cartesian_product(&edge_graph, &edge_graph);
+        assert_eq!(square.cores(), 4);
+        square.edges.sort();
+        assert_eq!(square.edges, Graph::hypercube(2).edges);
+
+        let mut cube = Graph::cartesian_product(&square, &edge_graph);
+        assert_eq!(cube.is_hypercube(), Some(3));
+        cube.nodes[0].core = false;
+        let mut cube4 = Graph::cartesian_product(&cube, &edge_graph);
+        assert_eq!(cube4.cores(), 14);
+        cube.corify();
+        assert_eq!(cube.cores(), 8);
+        cube4.corify();
+        assert_eq!(cube4.cores(), 16);
+    }
+
+    #[test]
+    fn to_dot() {
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        g.add_edge(a, b);
+        g.corify();
+        assert_eq!(g.to_dot("g"), [
+            "graph \"g\" {",
+            "    0 [style=filled fillcolor=black fontcolor=white];",
+            "    1 [style=filled fillcolor=black fontcolor=white];",
+            "    0 -- 1;",
+            "    0 -- 1 [style=dashed];",
+            "    1 -- 0 [style=dashed];",
+            "}\n",
+        ].join("\n"));
+
+        let mut g = Graph::hypercube(3);
+        g.corify();
+        let dot = g.to_dot("cube");
+        assert_eq!(dot.lines().filter(|l| l.contains("fillcolor")).count(), 8);
+        assert_eq!(dot.lines().filter(|l| l.contains("--")).count(), 12 + 8);
+        assert_eq!(dot.lines().filter(|l| l.ends_with("[style=dashed];")).count(), 8);
+    }
+
+    #[test]
+    fn all_pairs_distances() {
+        let mut g = Graph::hypercube(3);
+        let dist = g.all_pairs_distances();
+        for (i, row) in dist.iter().enumerate() {
+            assert_eq!(row[i], Some(0));
+            let expected: Vec<Option<u64>> = g.distance(i).unwrap().into_iter().map(|n| Some(n.1)).collect();
+            assert_eq!(row, &expected);
+        }
+        assert_eq!(dist[0][7], Some(3));
+
+        g.add_node(Node::new(false));
+        let dist = g.all_pairs_distances();
+        assert_eq!(dist[0][8], None);
+        assert_eq!(dist[8][8], Some(0));
+    }
+
+    #[test]
+    fn diameter() {
+        let g = Graph {
+            nodes: vec![Node::new(false); 4],
+            edges: vec![(0, 1), (1, 2), (2, 3)]
+        };
+        assert_eq!(g.diameter(), Some(3));
+        assert_eq!(g.eccentricity(0), Some(3));
+        assert_eq!(g.eccentricity(1), Some(2));
+        assert_eq!(Graph::hypercube(3).diameter(), Some(3));
+
+        let g = Graph {
+            nodes: vec![Node::new(false); 4],
+            edges: vec![(0, 1), (2, 3)]
+        };
+        assert_eq!(g.diameter(), None);
+        assert_eq!(g.eccentricity(0), None);
+        assert_eq!(Graph::new().diameter(), None);
+    }
+
+    #[test]
+    fn complement() {
+        let mut square = Graph::hypercube(2);
+        square.corify();
+        let g = Graph::complement(&square);
+        assert_eq!(g.edges, vec![(0, 3), (1, 2)]);
+        assert_eq!(g.cores(), 4);
+        assert_eq!(g.unique_edges(), 0);
+
+        let mut g = Graph::hypercube(3);
+        g.add_edge(2, 2);
+        let mut h = g.complement().complement();
+        g.remove_self_edges();
+        g.edges.sort();
+        h.edges.sort();
+        assert_eq!(g.edges, h.edges);
+        assert_eq!(Graph::complement(&Graph::hypercube(3)).edges.len(), 28 - 12);
+    }
+
+    #[test]
+    fn induced_subgraph() {
+        let mut g = Graph::hypercube(3);
+        g.corify();
+        let cores = g.cores();
+
+        let face = g.induced_subgraph(&[0, 1, 2, 3]);
+        assert_eq!(face.nodes.len(), 4);
+        assert_eq!(face.edges.len(), 4);
+        assert!(face.nodes.iter().all(|n| n.uniq.is_none()));
+
+        let nodes: Vec<usize> = (0..8).rev().collect();
+        let mut h = g.induced_subgraph(&nodes);
+        assert_eq!(h.nodes.len(), nodes.len());
+        assert_eq!(h.edges.len(), 12);
+        assert_eq!(h.cores(), cores);
+        h.corify();
+        assert_eq!(h.cores(), cores);
+        assert_eq!(h.unique_edges(), g.unique_edges());
+    }
+
+    #[test]
+    fn degree() {
+        let mut path = Graph::new();
+        let n = path.add_node(Node::new(false));
+        let m = path.add_node(Node::new(false));
+        let k = path.add_node(Node::new(false));
+        path.add_edge(n, m);
+        path.add_edge(m, k);
+        assert_eq!(path.degree(n), 1);
+        assert_eq!(path.degree(m), 2);
+        assert_eq!(path.degree_sequence(), vec![2, 1, 1]);
+        assert_eq!(path.max_degree(), 2);
+        assert_eq!(path.min_degree(), 1);
+
+        path.add_edge(k, n);
+        assert_eq!(path.degree_sequence(), vec![2, 2, 2]);
+        assert_eq!(path.min_degree(), 2);
+
+        let g = Graph::hypercube(3);
+        assert!((0..8).all(|i| g.degree(i) == 3));
+        assert_eq!(g.degree_sequence(), vec![3; 8]);
+        assert_eq!(g.max_degree(), 3);
+        assert_eq!(g.min_degree(), 3);
+    }
+
+    #[test]
+    fn validate() {
+        use AvatarGraphError::*;
+
+        let mut g = Graph::new();
+        let a = g.add_node(Node::new(false));
+        let b = g.add_node(Node::new(false));
+        assert_eq!(g.validate(a), Err(Disconnected(vec![b])));
         g.add_edge(a, b);
+        assert_eq!(g.validate(a), Ok(()));
+        let c = g.add_node(Node::new(false));
+        g.add_edge(b, c);
+        assert_eq!(g.validate(a), Err(Contractible(vec![c])));
+        g.remove_edge(b, c);
         g.add_edge(a, c);
-        g.add_edge(b, d);
-        g.add_edge(c, d);
-        g.corify();
-        assert_eq!(g.nodes[a].core, true);
-        assert_eq!(g.nodes[b].core, true);
-        assert_eq!(g.nodes[c].core, true);
-        assert_eq!(g.nodes[d].core, true);
-        assert_eq!(g.nodes[a].uniq, Some(d));
-        assert_eq!(g.nodes[b].uniq, Some(c));
-        assert_eq!(g.nodes[c].uniq, Some(b));
-        assert_eq!(g.nodes[d].uniq, Some(a));
+        assert_eq!(g.validate(a), Err(NonUniqueMaxAvatar(vec![b, c])));
+        assert!(!g.is_avatar_graph(a));
+
+        let mut g = Graph::new();
+        for _ in 0..5 {g.add_node(Node::new(false));}
+        g.add_edge(0, 2);
+        g.add_edge(0, 3);
+        g.add_edge(0, 4);
+        g.add_edge(1, 2);
+        g.add_edge(1, 3);
+        assert_eq!(g.validate(0), Err(NotUniversallyReachable(vec![4])));
+
+        let mut g = Graph::new();
+        for _ in 0..4 {g.add_node(Node::new(false));}
+        for i in 0..4 {
+            for j in i + 1..4 {g.add_edge(i, j);}
+        }
+        assert_eq!(g.validate(0), Err(AvatarConnectivityViolation(vec![1, 2])));
 
+        assert_eq!(Graph::hypercube(3).validate(0), Ok(()));
+    }
+
+    #[test]
+    fn girth() {
         let mut g = Graph::new();
         let a = g.add_node(Node::new(false));
         let b = g.add_node(Node::new(false));
         let c = g.add_node(Node::new(false));
         g.add_edge(a, b);
         g.add_edge(b, c);
+        assert_eq!(g.girth(), None);
+        let d = g.add_node(Node::new(false));
+        g.add_edge(b, d);
+        assert_eq!(g.girth(), None);
         g.add_edge(c, a);
-        g.corify();
-        assert_eq!(g.cores(), 0);
+        assert_eq!(g.girth(), Some(3));
+
+        let mut g = Graph::new();
+        for _ in 0..4 {g.add_node(Node::new(false));}
+        for i in 0..4 {g.add_edge(i, (i + 1) % 4);}
+        assert_eq!(g.girth(), Some(4));
+
+        assert_eq!(Graph::hypercube(2).girth(), Some(4));
+        assert_eq!(Graph::hypercube(4).girth(), Some(4));
+        assert_eq!(Graph::hypercube(1).girth(), None);
+
+        let g = Graph {
+            nodes: vec![Node::new(false); 3],
+            edges: vec![(0, 1), (0, 1), (1, 2), (2, 2)]
+        };
+        assert_eq!(g.girth(), None);
+        assert!(g.cycles().is_empty());
+        assert!(!g.has_cycle());
     }
 
     #[test]
-    fn corify_cube() {
-        let mut g = Graph::new();
-        let a000 = g.add_node(Node::new(false));
-        let a100 = g.add_node(Node::new(false));
-        let a010 = g.add_node(Node::new(false));
-        let a001 = g.add_node(Node::new(false));
-        let a011 = g.add_node(Node::new(false));
-        let a101 = g.add_node(Node::new(false));
-        let a110 = g.add_node(Node::new(false));
-        let a111 = g.add_node(Node::new(false));
-        g.add_edge(a000, a100);
-        g.add_edge(a000, a010);
-        g.add_edge(a000, a001);
-        g.add_edge(a100, a110);
-        g.add_edge(a100, a101);
-        g.add_edge(a010, a110);
-        g.add_edge(a010, a011);
-        g.add_edge(a001, a101);
-        g.add_edge(a001, a011);
-        g.add_edge(a011, a111);
-        g.add_edge(a101, a111);
-        g.add_edge(a110, a111);
-        g.corify();
-        assert_eq!(g.cores(), 8);
+    fn is_isomorphic() {
+        let cube = Graph::hypercube(3);
+        let mut g = cube.clone();
+        g.swap(0, 5);
+        g.swap(1, 7);
+        g.swap(2, 3);
+        assert!(g.edges != cube.edges);
+        assert!(g.is_isomorphic(&cube));
+        assert!(cube.is_isomorphic(&cube));
 
+        let wagner = named_graphs::wagner();
+        assert!(!wagner.is_isomorphic(&cube));
+        assert!(!cube.is_isomorphic(&wagner));
 
-        let mut g = Graph::new();
-        let a000 = g.add_node(Node::new(false));
-        let a110 = g.add_node(Node::new(false));
-        let a101 = g.add_node(Node::new(false));
-        let a100 = g.add_node(Node::new(false));
-        let a111 = g.add_node(Node::new(false));
-        let a010 = g.add_node(Node::new(false));
-        let a001 = g.add_node(Node::new(false));
-        let a011 = g.add_node(Node::new(false));
-        g.add_edge(a010, a011);
-        g.add_edge(a001, a011);
-        g.add_edge(a000, a010);
-        g.add_edge(a010, a110);
-        g.add_edge(a101, a111);
-        g.add_edge(a000, a001);
-        g.add_edge(a011, a111);
-        g.add_edge(a100, a110);
-        g.add_edge(a100, a101);
-        g.add_edge(a000, a100);
-        g.add_edge(a001, a101);
-        g.add_edge(a110, a111);
-        g.corify();
-        assert_eq!(g.cores(), 8);
+        // Two triangles and a 6-cycle have the same degree sequence.
+        let mut triangles = Graph::new();
+        let mut cycle = Graph::new();
+        for _ in 0..6 {
+            triangles.add_node(Node::new(false));
+            cycle.add_node(Node::new(false));
+        }
+        for i in 0..3 {
+            triangles.add_edge(i, (i + 1) % 3);
+            triangles.add_edge(i + 3, (i + 1) % 3 + 3);
+        }
+        for i in 0..6 {
+            cycle.add_edge(i, (i + 1) % 6);
+        }
+        assert_eq!(triangles.degree_sequence(), cycle.degree_sequence());
+        assert!(!triangles.is_isomorphic(&cycle));
+        assert!(!Graph::hypercube(2).is_isomorphic(&Graph::hypercube(3)));
     }
 
     #[test]
-    fn corify_cube4() {
-        let mut g = Graph {
-            nodes: vec![Node::new(false); 16],
-            edges: vec![
-                (0, 3), (2, 3), (1, 2), (0, 1),
-                (0, 4), (4, 7), (3, 7), (6, 7),
-                (2, 6), (5, 6), (1, 5), (4, 5),
-                (8, 15), (12, 15), (9, 12), (8, 9),
-                (9, 11), (10, 11), (8, 10), (10, 14),
-                (13, 14), (11, 13), (12, 13), (14, 15),
-                (4, 15), (5, 12), (1, 9), (0, 8),
-                (6, 13), (7, 14), (3, 10), (2, 11)
-            ]
-        };
-        g.corify();
-        assert_eq!(g.cores(), 16);
+    fn merge() {
+        let mut square = Graph::hypercube(2);
+        square.corify();
+        let cube = Graph::hypercube(3);
+        let g = Graph::merge(&square, &cube);
+        assert_eq!(g.nodes.len(), 12);
+        assert_eq!(g.edges.len(), 16);
+        assert_eq!(g.cores(), 4);
+        assert_eq!(g.nodes[0].uniq, square.nodes[0].uniq);
+        assert_eq!(g.connected_components().len(), 2);
+        assert!(g.edges[4..].iter().all(|&(a, b)| a >= 4 && b >= 4));
+
+        let mut g = square.clone();
+        let offset = g.merge_with_bridge(&square, 3, 0);
+        assert_eq!(offset, 4);
+        assert_eq!(g.nodes.len(), 8);
+        assert_eq!(g.edges.len(), 9);
+        assert!(g.edges.contains(&(3, 4)));
+        assert_eq!(g.nodes[4].uniq, square.nodes[0].uniq.map(|j| j + 4));
+        assert!(g.is_connected());
     }
 
     #[test]
-    fn corify_5() {
+    fn from_edges() {
+        let edges = vec![
+            (0, 1), (1, 2),
+            (2, 4), (3, 4),
+            (0, 3), (2, 3)
+        ];
         let mut g = Graph {
             nodes: vec![Node::new(false); 5],
-            edges: vec![
-                (0, 1), (1, 2),
-                (2, 4), (3, 4),
-                (0, 3), (2, 3)
-            ]
+            edges: edges.clone(),
         };
+        let mut h = Graph::from_edges(5, edges, vec![]);
+        assert_eq!(g.edges, h.edges);
+        assert_eq!(h.nodes.len(), 5);
+        assert_eq!(h.cores(), 0);
         g.corify();
-        assert_eq!(g.cores(), 2);
+        h.corify();
+        assert_eq!(h.cores(), g.cores());
+
+        let g = Graph::from_edges(3, vec![(1, 0), (2, 1), (0, 1)], vec![true, false]);
+        assert_eq!(g.edges, vec![(0, 1), (1, 2)]);
+        assert!(g.nodes[0].core);
+        assert!(!g.nodes[1].core);
+        assert!(!g.nodes[2].core);
+
+        let cube = Graph::hypercube(3);
+        let g = Graph::from_edges(8, cube.edges.iter().cloned(), std::iter::repeat(true));
+        assert_eq!(g.edges, cube.edges);
+        assert_eq!(g.cores(), 8);
     }
 
     #[test]
-    fn corify_7() {
-        let mut g = Graph {
-            //     __ 6 __
-            //   4 __   __  5
-            //   | __ 2 __  |
-            //   0 __   __  1
-            //        3
-            nodes: vec![Node::new(false); 7],
-            edges: vec![
-                (0, 3), (1, 3), (1, 2),
-                (0, 2), (0, 4), (2, 4),
-                (2, 5), (1, 5), (5, 6),
-                (4, 6)
-            ]
-        };
-        g.corify();
-        assert_eq!(g.cores(), 2);
+    fn automorphisms() {
+        let cube = Graph::hypercube(3);
+        let autos = cube.automorphisms();
+        assert_eq!(autos.len(), 48);
+        assert_eq!(autos[0], (0..8).collect::<Vec<usize>>());
+        for map in &autos {
+            let mut edges: Vec<(usize, usize)> = cube.edges.iter()
+                .map(|&(a, b)| (map[a].min(map[b]), map[a].max(map[b])))
+                .collect();
+            edges.sort();
+            edges.dedup();
+            assert_eq!(edges.len(), cube.edges.len());
+            assert!(edges.iter().all(|e| cube.edges.contains(e)));
+        }
+
+        let path = Graph::from_edges(3, vec![(0, 1), (1, 2)], vec![]);
+        assert_eq!(path.automorphisms(), vec![vec![0, 1, 2], vec![2, 1, 0]]);
+        assert_eq!(Graph::new().automorphisms(), vec![Vec::<usize>::new()]);
     }
 
     #[test]
-    fn wagner() {
-        //              1
-        //         6    |    7
-        //    2 ------- | ------- 3
-        //         5    |    4
-        //              0
-        let mut g = Graph {
-            nodes: vec![Node::new(false); 8],
-            edges: vec![
-                (0, 1), (2, 3), (5, 7), (4, 6),
-                (0, 4), (0, 5), (2, 5), (2, 6),
-                (1, 6), (1, 7), (3, 7), (3, 4)
-            ]
-        };
+    fn normalize() {
+        let mut cube = Graph::hypercube(3);
+        cube.corify();
+        let mut g = cube.clone();
+        g.swap(0, 5);
+        g.swap(1, 7);
+        g.swap(2, 3);
+        assert!(g != cube);
+        assert_eq!(g.normalize(), cube.normalize());
+        assert_eq!(g.canonical_hash(), cube.canonical_hash());
+        assert_eq!(cube.normalize().normalize(), cube.normalize());
+        assert_eq!(cube.normalize().cores(), 8);
+
+        let wagner = named_graphs::wagner();
+        let cube = Graph::hypercube(3);
+        assert_ne!(wagner.normalize(), cube.normalize());
+        assert_ne!(wagner.canonical_hash(), cube.canonical_hash());
+
+        // Core flags are part of the canonical form.
+        let mut path = Graph::from_edges(3, vec![(0, 1), (1, 2)], vec![true]);
+        let h = Graph::from_edges(3, vec![(0, 1), (1, 2)], vec![false, false, true]);
+        assert_eq!(path.normalize(), h.normalize());
+        path.nodes[1].core = true;
+        assert!(path.normalize() != h.normalize());
+
+        // Symmetric graphs are pruned by automorphisms.
+        assert_eq!(Graph::complete(12).normalize(), Graph::complete(12));
+        let mut k66 = named_graphs::complete_bipartite(6, 6);
+        k66.swap(0, 11);
+        assert_eq!(k66.normalize(), named_graphs::complete_bipartite(6, 6).normalize());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn corify_parallel() {
+        let mut g = Graph::hypercube(4);
+        g.add_node(Node::new(true));
+        g.add_edge(0, 16);
+        let mut h = g.clone();
         g.corify();
-        assert_eq!(g.cores(), 8);
+        h.corify_parallel();
+        assert_eq!(g, h);
     }
 
     #[test]
-    fn corify_8() {
-        //        0
-        //     4 _  _ 6
-        //  2   _ X _     3
-        //     7      5
-        //        1
-        let mut g = Graph {
-            nodes: vec![Node::new(false); 8],
-            edges: vec![
-                (0, 6), (3, 6), (3, 5),
-                (1, 5), (1, 7), (2, 7),
-                (2, 4), (0, 4), (4, 5),
-                (6, 7)
-            ]
-        };
+    fn avatar_extensions() {
+        let g = Graph::from_edges(2, vec![(0, 1)], vec![]);
+        assert!(g.avatar_extensions(0).is_empty());
+
+        let g = Graph::from_edges(3, vec![(0, 1), (0, 2)], vec![]);
+        assert_eq!(g.avatar_extensions(0), vec![(3, vec![1, 2])]);
+
+        let g = Graph::hypercube(2);
+        assert_eq!(g.avatar_extensions(0), vec![(4, vec![1, 3]), (4, vec![2, 3])]);
+    }
+
+    #[test]
+    fn cycles() {
+        let path = Graph::from_edges(4, vec![(0, 1), (1, 2), (2, 3)], vec![]);
+        assert!(path.cycles().is_empty());
+        assert!(!path.has_cycle());
+
+        let tree = Graph::from_edges(5, vec![(0, 1), (0, 2), (2, 3), (2, 4), (4, 4)], vec![]);
+        assert!(tree.cycles().is_empty());
+        assert!(!tree.has_cycle());
+
+        let triangle = Graph::from_edges(3, vec![(0, 1), (1, 2), (2, 0)], vec![]);
+        assert_eq!(triangle.cycles(), vec![vec![0, 1, 2]]);
+        assert!(triangle.has_cycle());
+
+        let square = Graph::from_edges(4, vec![(0, 1), (1, 2), (2, 3), (3, 0)], vec![]);
+        assert_eq!(square.cycles(), vec![vec![0, 1, 2, 3]]);
+        assert!(square.has_cycle());
+
+        let mut k4 = square.clone();
+        k4.add_edge(0, 2);
+        k4.add_edge(1, 3);
+        assert_eq!(k4.cycles().len(), 7);
+        let long: Vec<Vec<usize>> = k4.cycles().into_iter().filter(|c| c.len() == 4).collect();
+        assert_eq!(long, vec![vec![0, 1, 2, 3], vec![0, 1, 3, 2], vec![0, 2, 1, 3]]);
+        assert_eq!(Graph::hypercube(3).cycles().len(), 28);
+    }
+
+    #[test]
+    fn labels() {
+        let mut g: Graph<String> = Graph::default();
+        let a = g.add_node(Node::with_label(false, "a".into()));
+        let b = g.add_node(Node::with_label(false, "b".into()));
+        let c = g.add_node(Node::with_label(false, "c".into()));
+        let d = g.add_node(Node::with_label(false, "d".into()));
+        g.add_edge(a, b);
+        g.add_edge(b, c);
+        g.add_edge(c, d);
+        g.add_edge(d, a);
+        assert_eq!(g.nodes[b].label, "b");
+
+        let h = g.map_labels(|s| s.len());
+        assert_eq!(h.nodes.iter().map(|n| n.label).collect::<Vec<usize>>(), vec![1; 4]);
+        assert_eq!(h.edges, g.edges);
+        let h = g.map_labels(|s| s.to_uppercase());
+        assert_eq!(h.nodes[c].label, "C");
+
         g.corify();
-        assert_eq!(g.cores(), 8);
+        assert_eq!(g.cores(), 4);
+        let sub = g.induced_subgraph(&[c, b]);
+        assert_eq!(sub.nodes[0].label, "c");
+        assert_eq!(sub.nodes[1].uniq, None);
+
+        let mut h = g.clone();
+        h.swap(a, c);
+        assert!(g.is_isomorphic(&h));
+        assert_eq!(g.normalize(), h.normalize());
+        h.nodes[b].label = "e".into();
+        assert!(g.normalize() != h.normalize());
+        assert_eq!(g.map_labels(|_| ()).normalize(), h.map_labels(|_| ()).normalize());
     }
 
     #[test]
-    fn corify_9() {
-        //                   8
-        //              /          \
-        //          /                  \
-        //        0------1-------2-------3
-        //        |        \   /         |
-        //        |         \/           |
-        //        |         /\           |
-        //        |       /    \         |
-        //        4------5-------6-------7
-        //          \                  /
-        //              \         /
-        //                   9
-        let mut g = Graph {
-            nodes: vec![Node { core: false, uniq: None }; 10],
-            edges: vec![
-                (0, 8), (3, 8), (0, 1), (1, 2),
-                (2, 3), (0, 4), (1, 6), (2, 5),
-                (3, 7), (4, 5), (5, 6), (6, 7),
-                (4, 9), (7, 9)
-            ]
-        };
+    fn adjacency_list() {
+        let mut cube = Graph::hypercube(3);
+        cube.nodes[0].core = true;
+        let adj = cube.to_adjacency_list();
+        assert_eq!(adj[0], vec![1, 2, 4]);
+        assert_eq!(adj[7], vec![3, 5, 6]);
+        let cores: Vec<bool> = cube.nodes.iter().map(|n| n.core).collect();
+        assert_eq!(Graph::from_adjacency_list(&adj, &cores), Ok(cube));
+
+        let mut wagner = named_graphs::wagner();
+        let adj = wagner.to_adjacency_list();
+        assert!(adj.iter().all(|n| n.len() == 3));
+        let mut g = Graph::from_adjacency_list(&adj, &[false; 8]).unwrap();
+        wagner.edges.sort();
+        g.edges.sort();
+        assert_eq!(g, wagner);
+
+        assert!(Graph::from_adjacency_list(&[vec![1], vec![]], &[false; 2]).is_err());
+        assert!(Graph::from_adjacency_list(&[vec![2], vec![]], &[false; 2]).is_err());
+        assert!(Graph::from_adjacency_list(&[vec![1], vec![0]], &[false]).is_err());
+    }
+
+    #[test]
+    fn bipartite_parts() {
+        let square = Graph::from_edges(4, vec![(0, 1), (1, 2), (2, 3), (3, 0)], vec![]);
+        assert_eq!(square.bipartite_parts(), Some((vec![0, 2], vec![1, 3])));
+
+        let triangle = Graph::from_edges(3, vec![(0, 1), (1, 2), (2, 0)], vec![]);
+        assert_eq!(triangle.bipartite_parts(), None);
+
+        let (a, b) = Graph::hypercube(3).bipartite_parts().unwrap();
+        assert_eq!(a, vec![0, 3, 5, 6]);
+        assert_eq!(b, vec![1, 2, 4, 7]);
+
+        let g = Graph::from_edges(5, vec![(1, 2), (3, 4)], vec![]);
+        assert_eq!(g.bipartite_parts(), Some((vec![0, 1, 3], vec![2, 4])));
+        let g = Graph::from_edges(2, vec![(0, 1), (1, 1)], vec![]);
+        assert_eq!(g.bipartite_parts(), None);
+    }
+
+    #[test]
+    fn path_cycle_complete() {
+        let mut g = Graph::path(2);
         g.corify();
-        // assert_eq!(g.cores(), 4);
+        assert_eq!(g.cores(), 2);
+        assert_eq!(Graph::path(4).edges, vec![(0, 1), (1, 2), (2, 3)]);
+        assert_eq!(Graph::path(0).nodes.len(), 0);
+
+        let mut g = Graph::cycle(3);
+        g.corify();
+        assert_eq!(g.cores(), 0);
+        let mut g = Graph::cycle(4);
+        g.corify();
+        assert_eq!(g.cores(), 4);
+        assert_eq!(Graph::cycle(2).edges, vec![(0, 1)]);
+
+        assert_eq!(Graph::complete(4).edges.len(), 6);
+        assert_eq!(Graph::complete(4).degree_sequence(), vec![3; 4]);
+        assert_eq!(Graph::complete(1).edges.len(), 0);
     }
 
     #[test]
-    fn corify_10() {
-        //  0 ------- 1
-        //  |         |
-        //  2         |
-        // 4 3 ------ 5
-        let mut g = Graph {
-            nodes: vec![Node { core: false, uniq: None }; 6],
-            edges: vec![
-                (0, 1), (0, 2), (2, 4), (3, 4),
-                (2, 3), (3, 5), (1, 5)
-            ]
-        };
+    fn density_regular() {
+        let cube = Graph::hypercube(3);
+        assert_eq!(cube.is_regular(), Some(3));
+        assert!((cube.density() - 3.0 / 7.0).abs() < 1e-12);
+        assert_eq!(Graph::path(3).is_regular(), None);
+        assert_eq!(Graph::cycle(5).is_regular(), Some(2));
+        assert_eq!(Graph::complete(5).density(), 1.0);
+        assert_eq!(Graph::path(1).density(), 0.0);
+        assert_eq!(Graph::path(1).is_regular(), Some(0));
+        assert_eq!(Graph::new().is_regular(), None);
+    }
+
+    #[test]
+    fn traversal() {
+        //     0
+        //    / \
+        //   1   2
+        //  / \   \
+        // 3   4   5
+        let tree = Graph::from_edges(6, vec![(0, 2), (0, 1), (1, 3), (1, 4), (2, 5)], vec![]);
+        assert_eq!(tree.bfs(0).collect::<Vec<usize>>(), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(tree.dfs(0).collect::<Vec<usize>>(), vec![0, 1, 3, 4, 2, 5]);
+        assert_eq!(tree.bfs(4).collect::<Vec<usize>>(), vec![4, 1, 0, 3, 2, 5]);
+        assert_eq!(tree.bfs_with_depth(0).collect::<Vec<(usize, u64)>>(),
+            vec![(0, 0), (1, 1), (2, 1), (3, 2), (4, 2), (5, 2)]);
+
+        let cube = Graph::hypercube(3);
+        for start in 0..8 {
+            let mut nodes: Vec<usize> = cube.dfs(start).collect();
+            nodes.sort();
+            assert_eq!(nodes, (0..8).collect::<Vec<usize>>());
+            let mut depth: Vec<(usize, u64)> = cube.bfs_with_depth(start).collect();
+            depth.sort();
+            assert_eq!(Ok(depth), cube.distance(start));
+        }
+
+        let g = Graph::from_edges(4, vec![(0, 1), (2, 3)], vec![]);
+        assert_eq!(g.bfs(1).collect::<Vec<usize>>(), vec![1, 0]);
+        assert_eq!(g.dfs(3).collect::<Vec<usize>>(), vec![3, 2]);
+    }
+
+    #[test]
+    fn repair_unique_edges() {
+        let mut g = Graph::hypercube(3);
         g.corify();
-        // assert_eq!(g.cores(), 3);
+        assert!(g.unique_edges_valid());
+        let expected = g.clone();
+
+        g.nodes[0].uniq = Some(1);
+        g.nodes[3].uniq = Some(42);
+        assert!(!g.unique_edges_valid());
+        g.repair_unique_edges();
+        assert!(g.unique_edges_valid());
+        assert_eq!(g, expected);
+
+        let mut g = Graph::path(3);
+        g.nodes[0].uniq = Some(2);
+        g.nodes[1].uniq = Some(7);
+        assert!(!g.unique_edges_valid());
+        g.repair_unique_edges();
+        assert!(g.unique_edges_valid());
+        let mut h = Graph::path(3);
+        h.corify();
+        assert_eq!(g, h);
+
+        let mut g = Graph::hypercube(2);
+        g.corify();
+        g.remove_node(3);
+        g.repair_unique_edges();
+        assert!(g.unique_edges_valid());
+        assert_eq!(g.unique_edges(), 0);
+    }
+
+    #[test]
+    fn avatar_cache() {
+        let g = Graph::hypercube(3);
+        let mut cache = g.build_cache();
+        for i in 0..8 {
+            assert_eq!(cache.get(i), &g.avatar_distance(i)[..]);
+            assert!(g.is_avatar_graph_cached(i, &cache));
+        }
+        cache.invalidate(2);
+        assert!(cache.get(2).is_empty());
+        assert!(g.is_avatar_graph_cached(2, &cache));
+
+        // Invalidated entries are recomputed after changing the graph.
+        let mut h = g.clone();
+        h.swap(0, 1);
+        h.remove_edge(0, 2);
+        h.add_edge(0, 7);
+        for i in 0..8 {cache.invalidate(i)}
+        for i in 0..8 {
+            assert_eq!(h.validate_cached(i, &cache), h.validate(i));
+        }
+
+        let g = named_graphs::complete_bipartite(2, 3);
+        let cache = g.build_cache();
+        for i in 0..5 {
+            assert_eq!(g.validate_cached(i, &cache), g.validate(i));
+        }
+        let g = Graph::complete(4);
+        let cache = g.build_cache();
+        assert_eq!(g.validate_cached(0, &cache), g.validate(0));
+    }
+
+    #[test]
+    fn stable_hasher() {
+        let mut hasher = StableHasher::new();
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+        let mut hasher = StableHasher::new();
+        1u32.hash(&mut hasher);
+        let mut other = StableHasher::new();
+        other.write(&[1, 0, 0, 0]);
+        assert_eq!(hasher.finish(), other.finish());
+        assert_eq!(Graph::hypercube(3).wl_hash(3), named_graphs::wagner().wl_hash(3));
     }
 }
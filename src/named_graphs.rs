@@ -0,0 +1,66 @@
+//! Constructors for well known graphs.
+//!
+//! All nodes are non-cores, such that `corify` can be called directly.
+
+use crate::Graph;
+
+/// Creates the Wagner graph.
+///
+/// This is a filled Avatar Graph with 8 nodes that is not a hypercube.
+pub fn wagner() -> Graph {
+    Graph::from_edges(8, vec![
+        (0, 1), (2, 3), (5, 7), (4, 6),
+        (0, 4), (0, 5), (2, 5), (2, 6),
+        (1, 6), (1, 7), (3, 7), (3, 4)
+    ], vec![])
+}
+
+/// Creates the Petersen graph.
+///
+/// Nodes `0..5` form the outer cycle and nodes `5..10` the inner star.
+pub fn petersen() -> Graph {
+    Graph::from_edges(10, vec![
+        (0, 1), (1, 2), (2, 3), (3, 4), (0, 4),
+        (5, 7), (7, 9), (6, 9), (6, 8), (5, 8),
+        (0, 5), (1, 6), (2, 7), (3, 8), (4, 9)
+    ], vec![])
+}
+
+/// Creates the complete bipartite graph `K_{m,n}`.
+///
+/// Nodes `0..m` are connected to all nodes `m..m + n`.
+pub fn complete_bipartite(m: usize, n: usize) -> Graph {
+    Graph::from_edges(m + n, (0..m).flat_map(|i| (m..m + n).map(move |j| (i, j))), vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wagner_corify() {
+        let mut g = wagner();
+        assert_eq!(g.cores(), 0);
+        g.corify();
+        assert_eq!(g.cores(), 8);
+        assert!(!g.is_isomorphic(&Graph::hypercube(3)));
+    }
+
+    #[test]
+    fn petersen_girth() {
+        let g = petersen();
+        assert_eq!(g.edges.len(), 15);
+        assert_eq!(g.is_regular(), Some(3));
+        assert_eq!(g.girth(), Some(5));
+    }
+
+    #[test]
+    fn complete_bipartite_edges() {
+        let g = complete_bipartite(3, 3);
+        assert_eq!(g.nodes.len(), 6);
+        assert_eq!(g.edges.len(), 9);
+        assert_eq!(g.bipartite_parts(), Some((vec![0, 1, 2], vec![3, 4, 5])));
+        assert!(complete_bipartite(2, 2).is_isomorphic(&Graph::cycle(4)));
+        assert_eq!(complete_bipartite(0, 3).edges.len(), 0);
+    }
+}
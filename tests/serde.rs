@@ -0,0 +1,12 @@
+use avatar_graph::*;
+
+#[test]
+fn serde() {
+    let mut g = Graph::hypercube(3);
+    g.corify();
+    let json = serde_json::to_string(&g).unwrap();
+    let mut h: Graph = serde_json::from_str(&json).unwrap();
+    assert_eq!(h, g);
+    h.corify();
+    assert_eq!(h.cores(), 8);
+}